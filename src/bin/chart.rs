@@ -1,16 +1,18 @@
 use gnui::{
     backend::{backend_from_os_str, Backend},
+    keymap::Keymap,
     terminal,
     threads::input::InputThread,
     threads::{
+        control::ControlThread,
         event::event_thread,
         main::{main_thread, InputBuffer},
     },
-    widgets::chart::Chart,
+    widgets::chart::{self, Chart},
     Result,
 };
 
-use clap::Parser;
+use clap::{CommandFactory, ErrorKind, Parser};
 
 /// Display text input as a TUI chart
 #[derive(Debug, Parser)]
@@ -19,6 +21,12 @@ struct Cli {
     #[clap(flatten)]
     input_thread: InputThread,
 
+    #[clap(flatten)]
+    control_thread: ControlThread,
+
+    #[clap(flatten)]
+    keymap: Keymap,
+
     /// Output file; if -, write to standard output
     #[clap(parse(try_from_os_str = backend_from_os_str), default_value = "-")]
     output: Backend,
@@ -30,17 +38,28 @@ struct Cli {
 fn main() -> Result {
     let Cli {
         input_thread,
+        control_thread,
+        keymap,
         output,
         chart,
     } = Cli::parse();
 
+    if let Err(message) = input_thread.validate() {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+
+    let control_rx = control_thread.spawn()?;
     let buf: InputBuffer = Default::default();
 
     main_thread(
         &mut terminal(output)?,
         input_thread.spawn(),
         event_thread(),
+        control_rx,
+        keymap.bindings(chart::DEFAULT_BINDINGS),
         buf.clone(),
-        chart.draw(buf),
+        chart.view(buf),
     )
 }
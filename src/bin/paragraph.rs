@@ -1,13 +1,14 @@
 use gnui::{
     backend::{backend_from_os_str, Backend},
+    keymap::Keymap,
     terminal,
     threads::main::InputBuffer,
-    threads::{event::event_thread, input::InputThread, main::main_thread},
-    widgets::paragraph::Paragraph,
+    threads::{control::ControlThread, event::event_thread, input::InputThread, main::main_thread},
+    widgets::paragraph::{self, Paragraph},
     Result,
 };
 
-use clap::Parser;
+use clap::{CommandFactory, ErrorKind, Parser};
 
 /// Display text input as a TUI paragraph
 #[derive(Debug, Parser)]
@@ -16,6 +17,12 @@ struct Cli {
     #[clap(flatten)]
     input_thread: InputThread,
 
+    #[clap(flatten)]
+    control_thread: ControlThread,
+
+    #[clap(flatten)]
+    keymap: Keymap,
+
     /// Output file; if -, write to standard output
     #[clap(parse(try_from_os_str = backend_from_os_str), default_value = "-")]
     output: Backend,
@@ -27,17 +34,28 @@ struct Cli {
 fn main() -> Result {
     let Cli {
         input_thread,
+        control_thread,
+        keymap,
         output,
         paragraph,
     } = Cli::parse();
 
+    if let Err(message) = input_thread.validate() {
+        Cli::command()
+            .error(ErrorKind::ArgumentConflict, message)
+            .exit();
+    }
+
+    let control_rx = control_thread.spawn()?;
     let buf: InputBuffer = Default::default();
 
     main_thread(
         &mut terminal(output)?,
         input_thread.spawn(),
         event_thread(),
+        control_rx,
+        keymap.bindings(paragraph::DEFAULT_BINDINGS),
         buf.clone(),
-        paragraph.draw(buf),
+        paragraph.view(buf),
     )
 }
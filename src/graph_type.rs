@@ -0,0 +1,17 @@
+use clap::ArgEnum;
+use tui::widgets::GraphType as TuiGraphType;
+
+#[derive(Debug, Copy, Clone, ArgEnum)]
+pub enum GraphType {
+    Scatter,
+    Line,
+}
+
+impl From<GraphType> for TuiGraphType {
+    fn from(graph_type: GraphType) -> Self {
+        match graph_type {
+            GraphType::Scatter => TuiGraphType::Scatter,
+            GraphType::Line => TuiGraphType::Line,
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use clap::Args;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::threads::control::Command;
+
+/// Commands bound to keys, and whether mouse events are captured
+#[derive(Debug, Default, Clone)]
+pub struct Bindings {
+    pub keys: HashMap<KeyEvent, Command>,
+    pub mouse: bool,
+}
+
+/// Parse a key such as `j`, `G`, `pagedown`, `f5` or `ctrl-d`
+pub fn key_from_str(key: &str) -> Result<KeyEvent, String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut code = key;
+
+    while code.chars().count() > 1 {
+        if let Some(rest) = code.strip_prefix("ctrl-") {
+            modifiers |= KeyModifiers::CONTROL;
+            code = rest;
+        } else if let Some(rest) = code.strip_prefix("alt-") {
+            modifiers |= KeyModifiers::ALT;
+            code = rest;
+        } else if let Some(rest) = code.strip_prefix("shift-") {
+            modifiers |= KeyModifiers::SHIFT;
+            code = rest;
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = code.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match code.to_ascii_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            f => f
+                .strip_prefix('f')
+                .and_then(|n| n.parse().ok())
+                .map(KeyCode::F)
+                .ok_or_else(|| format!("Invalid key: {key}"))?,
+        },
+    };
+
+    Ok(normalize(KeyEvent::new(code, modifiers)))
+}
+
+/// Give keys one form whichever way they're written or reported.
+/// Shift-Tab is reported as BackTab, with or without the shift modifier depending on the platform,
+/// and uppercase characters may or may not come with it.
+pub fn normalize(key: KeyEvent) -> KeyEvent {
+    match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::SHIFT) => KeyEvent::new(
+            KeyCode::Char(c.to_ascii_uppercase()),
+            key.modifiers - KeyModifiers::SHIFT,
+        ),
+        KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
+            KeyEvent::new(KeyCode::BackTab, key.modifiers)
+        }
+        KeyCode::BackTab => KeyEvent::new(KeyCode::BackTab, key.modifiers | KeyModifiers::SHIFT),
+        _ => key,
+    }
+}
+
+/// A key and the command it runs; no command unbinds the key
+#[derive(Debug, Clone)]
+pub struct Binding {
+    key: KeyEvent,
+    command: Option<Command>,
+}
+
+fn binding_from_str(binding: &str) -> Result<Binding, String> {
+    // Skip the first character so = itself can be bound
+    let (key, command) = binding
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '=')
+        .map(|(i, _)| (&binding[..i], &binding[i + 1..]))
+        .ok_or_else(|| format!("Expected <KEY>=<COMMAND>: {binding}"))?;
+
+    Ok(Binding {
+        key: key_from_str(key.trim())?,
+        command: match command.trim() {
+            "none" => None,
+            command => Some(command.parse()?),
+        },
+    })
+}
+
+#[derive(Debug, Default, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-KEYMAP")]
+pub struct Keymap {
+    /// Bind a key to a runtime command, as <KEY>=<COMMAND>, e.g. 'ctrl-d=scroll-y +10'.
+    /// Keys may be prefixed with ctrl-, alt- or shift-; binding to none removes a key.
+    #[clap(
+        name = "BIND",
+        long = "bind",
+        multiple_occurrences = true,
+        parse(try_from_str = binding_from_str)
+    )]
+    bindings: Vec<Binding>,
+
+    /// Quit on q or Esc
+    #[clap(name = "QUIT_KEYS", long = "quit-keys")]
+    quit_keys: bool,
+
+    /// Start without the default scroll bindings
+    #[clap(name = "NO_DEFAULT_BINDINGS", long = "no-default-bindings")]
+    no_default_bindings: bool,
+
+    /// Capture the mouse; the wheel scrolls paragraphs and zooms charts,
+    /// and clicking a chart legend entry shows or hides its series
+    #[clap(name = "MOUSE", long = "mouse")]
+    mouse: bool,
+}
+
+impl Keymap {
+    /// Resolve the final bindings, starting from a widget's `(key, command)` defaults
+    pub fn bindings(self, defaults: &[(&str, &str)]) -> Bindings {
+        let mut keys = HashMap::new();
+
+        if !self.no_default_bindings {
+            keys.extend(defaults.iter().map(|(key, command)| {
+                (
+                    key_from_str(key).expect("Invalid default key"),
+                    command.parse().expect("Invalid default command"),
+                )
+            }));
+        }
+
+        if self.quit_keys {
+            keys.insert(KeyEvent::from(KeyCode::Char('q')), Command::Quit);
+            keys.insert(KeyEvent::from(KeyCode::Esc), Command::Quit);
+        }
+
+        for Binding { key, command } in self.bindings {
+            match command {
+                Some(command) => keys.insert(key, command),
+                None => keys.remove(&key),
+            };
+        }
+
+        Bindings {
+            keys,
+            mouse: self.mouse,
+        }
+    }
+}
@@ -1,9 +1,5 @@
 // TODO: Prevent event and input thread panics when main exits
 // TODO: Finish chart implementation
-// TODO: Investigate using serde to deserialize input
-//       * Could provide free support for various formats
-// TODO: Investigate using a second input stream for runtime commands
-//       * ex. Paragraph scrolling
 
 use backend::Backend;
 use threads::main::Terminal;
@@ -12,8 +8,14 @@ use tui::backend::CrosstermBackend;
 pub mod alignment;
 pub mod backend;
 pub mod border;
+pub mod graph_type;
+pub mod keymap;
+pub mod marker;
 pub mod path;
+pub mod pattern;
+pub mod record;
 pub mod style;
+pub mod text;
 pub mod threads;
 pub mod widgets;
 
@@ -0,0 +1,23 @@
+use clap::ArgEnum;
+use tui::symbols::Marker as TuiMarker;
+
+#[derive(Debug, Copy, Clone, ArgEnum)]
+pub enum Marker {
+    Dot,
+    Block,
+    /// Vertical bars rising to each sample
+    Bar,
+    Braille,
+}
+
+impl From<Marker> for TuiMarker {
+    fn from(marker: Marker) -> Self {
+        match marker {
+            Marker::Dot => TuiMarker::Dot,
+            Marker::Block => TuiMarker::Block,
+            // Bars aren't a tui marker, so charts draw them separately
+            Marker::Bar => TuiMarker::Block,
+            Marker::Braille => TuiMarker::Braille,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::str::FromStr;
+
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+
+/// A name pattern: a glob such as `Tccd*`, or a regular expression between slashes such as `/^CPU Tccd\d$/`.
+///
+/// Globs match whole names and support `*`, `?`, `[...]` classes and `{a,b}` alternatives.
+/// Regular expressions match anywhere unless anchored, with the syntax of the `regex` crate.
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source
+            .strip_prefix('/')
+            .and_then(|regex| regex.strip_suffix('/'))
+        {
+            Some(regex) => Regex::new(regex)
+                .map(Pattern::Regex)
+                .map_err(|e| format!("Invalid regex {source}: {e}")),
+            None => Glob::new(source)
+                .map(|glob| Pattern::Glob(glob.compile_matcher()))
+                .map_err(|e| format!("Invalid glob {source}: {e}")),
+        }
+    }
+}
+
+impl Pattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Pattern::Glob(glob) => glob.is_match(name),
+            Pattern::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(source: &str) -> Pattern {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn glob_matches_whole_names() {
+        let glob = pattern("CPU Tccd*");
+        assert!(glob.matches("CPU Tccd1"));
+        assert!(!glob.matches("GPU CPU Tccd1"));
+
+        let glob = pattern("Tccd[!1]");
+        assert!(!glob.matches("Tccd1"));
+        assert!(glob.matches("Tccd2"));
+
+        // Regex syntax means nothing in a glob
+        assert!(pattern("a.b").matches("a.b"));
+        assert!(!pattern("a.b").matches("axb"));
+    }
+
+    #[test]
+    fn regex_matches_anywhere_unless_anchored() {
+        let regex = pattern("/Tccd/");
+        assert!(regex.matches("CPU Tccd1"));
+        assert!(!regex.matches("CPU Tctl"));
+
+        let regex = pattern(r"/^CPU Tccd\d$/");
+        assert!(regex.matches("CPU Tccd1"));
+        assert!(!regex.matches("CPU Tccd12"));
+    }
+
+    #[test]
+    fn errors() {
+        assert!("/*a/".parse::<Pattern>().is_err());
+        assert!("[abc".parse::<Pattern>().is_err());
+    }
+}
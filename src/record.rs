@@ -0,0 +1,774 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+    time::{Instant, SystemTime},
+};
+
+use clap::{ArgEnum, Args};
+use csv::StringRecord;
+use serde::Deserialize;
+
+use crate::pattern::Pattern;
+
+/// A single delimited unit of input, parsed according to an [`InputFormat`]
+#[derive(Debug, Default, Clone)]
+pub struct Record {
+    /// Human-readable text for display
+    pub text: String,
+    /// Named numeric values extracted from the input
+    pub samples: Vec<Sample>,
+    /// Problem encountered while producing this record, for display alongside it
+    pub error: Option<String>,
+    /// Connection the record arrived on, when reading from a listener
+    pub source: Option<String>,
+    /// When the record was read
+    pub time: Option<SystemTime>,
+}
+
+/// A named numeric value
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Sample {
+    pub name: String,
+    pub value: f64,
+    /// Explicit X coordinate, if the input provides one
+    #[serde(default)]
+    pub x: Option<f64>,
+    /// Unit of the value, such as °C
+    #[serde(default)]
+    pub unit: Option<String>,
+}
+
+impl std::fmt::Display for Sample {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = with_unit(self.value.to_string(), self.unit.as_deref());
+        write!(f, "{}: {value}", self.name)
+    }
+}
+
+/// Follow a value with its unit, separated by a space unless the unit is a symbol like % or °C
+pub fn with_unit(value: String, unit: Option<&str>) -> String {
+    match unit {
+        Some(unit) if unit.starts_with(['%', '°']) => format!("{value}{unit}"),
+        Some(unit) => format!("{value} {unit}"),
+        None => value,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(Sample),
+    Many(Vec<Sample>),
+}
+
+#[derive(Debug, Copy, Clone, ArgEnum)]
+pub enum InputFormat {
+    /// Lines of <VALUE>\t<NAME>
+    Text,
+    /// Lines of <X>\t<Y>\t<NAME>
+    Xy,
+    /// A JSON sample object or array of sample objects
+    Json,
+    /// One JSON sample object per line
+    Ndjson,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Tab-separated values with a header row
+    Tsv,
+}
+
+#[derive(Debug, Default, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-INPUT-COLUMNS")]
+pub struct Columns {
+    /// CSV / TSV columns to read values from; defaults to every numeric column
+    #[clap(name = "VALUE_COL", long = "value-col", multiple_values = true)]
+    values: Vec<String>,
+
+    /// CSV / TSV column to read sample names from
+    #[clap(name = "LABEL_COL", long = "label-col")]
+    label: Option<String>,
+
+    /// CSV / TSV column to read X coordinates from
+    #[clap(name = "X_COL", long = "x-col")]
+    x: Option<String>,
+}
+
+impl Columns {
+    fn samples(&self, header: &StringRecord, row: &StringRecord) -> Vec<Sample> {
+        let index = |name: &str| header.iter().position(|column| column == name);
+
+        let label_index = self.label.as_deref().and_then(index);
+        let x_index = self.x.as_deref().and_then(index);
+
+        let label = label_index.and_then(|i| row.get(i));
+        let x = x_index
+            .and_then(|i| row.get(i))
+            .and_then(|x| x.parse().ok());
+
+        let values = if self.values.is_empty() {
+            (0..header.len())
+                .filter(|i| Some(*i) != label_index && Some(*i) != x_index)
+                .collect::<Vec<_>>()
+        } else {
+            self.values.iter().filter_map(|name| index(name)).collect()
+        };
+
+        values
+            .iter()
+            .filter_map(|i| {
+                let value = row.get(*i)?.parse().ok()?;
+                let column = header.get(*i)?;
+
+                let name = match label {
+                    Some(label) if values.len() == 1 => label.to_string(),
+                    Some(label) => format!("{label} {column}"),
+                    None => column.to_string(),
+                };
+
+                Some(Sample {
+                    name,
+                    value,
+                    x,
+                    unit: None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A value for samples whose names match a pattern, or for every sample, as [<PATTERN>=]<VALUE>
+#[derive(Debug, Clone)]
+pub struct Rule<T> {
+    pattern: Option<Pattern>,
+    value: T,
+}
+
+fn rule_from_str<T>(rule: &str) -> Result<Rule<T>, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let (pattern, value) = match rule.rsplit_once('=') {
+        Some((pattern, value)) => (Some(pattern.parse()?), value),
+        None => (None, rule),
+    };
+
+    Ok(Rule {
+        pattern,
+        value: value
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid value in {rule:?}: {e}"))?,
+    })
+}
+
+/// The value of the last rule matching `name`
+fn find<'a, T>(rules: &'a [Rule<T>], name: &str) -> Option<&'a T> {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| {
+            rule.pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.matches(name))
+        })
+        .map(|rule| &rule.value)
+}
+
+#[derive(Debug, Default, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-INPUT-TRANSFORMS")]
+pub struct Transforms {
+    /// Convert counters to per-second rates of change between records, by arrival time.
+    /// A counter that decreases is taken to have been reset to zero.
+    /// Applies to every sample, or with --rate=PATTERN only to those whose names match
+    #[clap(
+        name = "RATE",
+        long = "rate",
+        multiple_occurrences = true,
+        min_values = 0,
+        require_equals = true
+    )]
+    rates: Option<Vec<Pattern>>,
+
+    /// Multiply sample values by a factor, as [PATTERN=]FACTOR, such as 0.001 or 'CPU*=0.001'.
+    /// The last rule matching a sample's name applies
+    #[clap(
+        name = "SCALE",
+        long = "scale",
+        multiple_occurrences = true,
+        allow_hyphen_values = true,
+        parse(try_from_str = rule_from_str)
+    )]
+    scales: Vec<Rule<f64>>,
+
+    /// Add to sample values after scaling, as [PATTERN=]OFFSET
+    #[clap(
+        name = "OFFSET",
+        long = "offset",
+        multiple_occurrences = true,
+        allow_hyphen_values = true,
+        parse(try_from_str = rule_from_str)
+    )]
+    offsets: Vec<Rule<f64>>,
+
+    /// Unit of sample values, as [PATTERN=]UNIT, shown in chart axes and legends
+    #[clap(
+        name = "UNIT",
+        long = "unit",
+        multiple_occurrences = true,
+        parse(try_from_str = rule_from_str)
+    )]
+    units: Vec<Rule<String>>,
+
+    /// Latest value and arrival of each counter, by sample name.
+    /// Arrivals are measured on the monotonic clock, so wall clock changes don't skew rates
+    #[clap(skip)]
+    counters: HashMap<String, (f64, Instant)>,
+}
+
+impl Transforms {
+    fn is_empty(&self) -> bool {
+        self.rates.is_none()
+            && self.scales.is_empty()
+            && self.offsets.is_empty()
+            && self.units.is_empty()
+    }
+
+    /// Transform samples that arrived at `arrival`.
+    /// Counters seen for the first time are dropped, having no rate yet.
+    fn apply(&mut self, samples: &mut Vec<Sample>, arrival: Instant) {
+        if let Some(patterns) = &self.rates {
+            let counters = &mut self.counters;
+            samples.retain_mut(|sample| {
+                if !patterns.is_empty() && !patterns.iter().any(|p| p.matches(&sample.name)) {
+                    return true;
+                }
+
+                let counter = sample.value;
+                let previous = counters.insert(sample.name.clone(), (counter, arrival));
+                let (previous, since) = match previous {
+                    Some(previous) => previous,
+                    None => return false,
+                };
+
+                let elapsed = arrival.duration_since(since);
+                if elapsed.is_zero() {
+                    return false;
+                }
+
+                let delta = if counter < previous {
+                    counter
+                } else {
+                    counter - previous
+                };
+                sample.value = delta / elapsed.as_secs_f64();
+                true
+            });
+        }
+
+        for sample in samples {
+            if let Some(scale) = find(&self.scales, &sample.name) {
+                sample.value *= scale;
+            }
+            if let Some(offset) = find(&self.offsets, &sample.name) {
+                sample.value += offset;
+            }
+            if let Some(unit) = find(&self.units, &sample.name) {
+                sample.unit = Some(unit.clone());
+            }
+        }
+    }
+}
+
+/// Converts raw input into [`Record`]s, retaining any state the format needs between records
+#[derive(Debug, Clone)]
+pub struct RecordParser {
+    format: InputFormat,
+    columns: Columns,
+    transforms: Transforms,
+    header: Option<StringRecord>,
+}
+
+impl RecordParser {
+    pub fn new(format: InputFormat, columns: Columns, transforms: Transforms) -> Self {
+        RecordParser {
+            format,
+            columns,
+            transforms,
+            header: None,
+        }
+    }
+
+    pub fn parse(&mut self, input: String) -> Record {
+        let time = SystemTime::now();
+        let arrival = Instant::now();
+        let mut record = match self.format {
+            InputFormat::Text => parse_text(input),
+            InputFormat::Xy => parse_xy(input),
+            InputFormat::Json => parse_json(input),
+            InputFormat::Ndjson => parse_ndjson(input),
+            InputFormat::Csv => self.parse_delimited(input, b','),
+            InputFormat::Tsv => self.parse_delimited(input, b'\t'),
+        };
+
+        if !self.transforms.is_empty() {
+            self.transforms.apply(&mut record.samples, arrival);
+
+            // Text written from samples shows them transformed; raw text input is left as it was
+            if let InputFormat::Json | InputFormat::Ndjson | InputFormat::Csv | InputFormat::Tsv =
+                self.format
+            {
+                if record.error.is_none() {
+                    record.text = samples_text(&record.samples);
+                }
+            }
+        }
+
+        Record {
+            time: Some(time),
+            ..record
+        }
+    }
+
+    /// Parse delimited rows, taking the first row seen as the header.
+    /// Repeated header rows in later records are skipped.
+    fn parse_delimited(&mut self, input: String, delimiter: u8) -> Record {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(input.as_bytes());
+
+        let mut samples = vec![];
+        for row in reader.records() {
+            let row = match row {
+                Ok(row) => row,
+                Err(e) => return Record::invalid(input, e),
+            };
+
+            match &self.header {
+                None => self.header = Some(row),
+                Some(header) if *header == row => (),
+                Some(header) => samples.extend(self.columns.samples(header, &row)),
+            }
+        }
+
+        samples.into()
+    }
+}
+
+fn parse_text(input: String) -> Record {
+    let samples = input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let value = parts.next()?.parse::<f64>().ok()?;
+            Some(Sample {
+                name: parts.collect(),
+                value,
+                x: None,
+                unit: None,
+            })
+        })
+        .collect();
+
+    Record {
+        text: input,
+        samples,
+        ..Default::default()
+    }
+}
+
+fn parse_xy(input: String) -> Record {
+    let samples = input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let x = parts.next()?.parse::<f64>().ok()?;
+            let value = parts.next()?.parse::<f64>().ok()?;
+            Some(Sample {
+                name: parts.collect(),
+                value,
+                x: Some(x),
+                unit: None,
+            })
+        })
+        .collect();
+
+    Record {
+        text: input,
+        samples,
+        ..Default::default()
+    }
+}
+
+fn parse_json(input: String) -> Record {
+    match serde_json::from_str::<OneOrMany>(&input) {
+        Ok(OneOrMany::One(sample)) => vec![sample].into(),
+        Ok(OneOrMany::Many(samples)) => samples.into(),
+        Err(e) => Record::invalid(input, e),
+    }
+}
+
+fn parse_ndjson(input: String) -> Record {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Sample>, _>>()
+        .map(Into::into)
+        .unwrap_or_else(|e| Record::invalid(input, e))
+}
+
+impl Record {
+    /// A record carrying nothing but an error
+    pub fn error(error: impl ToString) -> Self {
+        Record {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Keep unparseable input as plain text, alongside the reason it was rejected
+    fn invalid(text: String, error: impl ToString) -> Self {
+        Record {
+            error: Some(error.to_string()),
+            ..text.into()
+        }
+    }
+}
+
+impl From<Vec<Sample>> for Record {
+    fn from(samples: Vec<Sample>) -> Self {
+        Record {
+            text: samples_text(&samples),
+            samples,
+            ..Default::default()
+        }
+    }
+}
+
+fn samples_text(samples: &[Sample]) -> String {
+    samples
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl From<String> for Record {
+    fn from(text: String) -> Self {
+        Record {
+            text,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parser(format: InputFormat) -> RecordParser {
+        RecordParser::new(format, Columns::default(), Transforms::default())
+    }
+
+    fn sample(name: &str, value: f64) -> Sample {
+        Sample {
+            name: name.to_string(),
+            value,
+            x: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn text_skips_lines_without_values() {
+        let input = "54.1\tCPU Tctl\nnot a number\n41\tCPU Tccd1\n";
+        let record = parser(InputFormat::Text).parse(input.to_string());
+
+        assert_eq!(
+            record.samples,
+            vec![sample("CPU Tctl", 54.1), sample("CPU Tccd1", 41.0)]
+        );
+        assert_eq!(record.text, input);
+        assert!(record.error.is_none());
+        assert!(record.time.is_some());
+    }
+
+    #[test]
+    fn text_name_keeps_later_tabs() {
+        let record = parser(InputFormat::Text).parse("1\ta\tb".to_string());
+        assert_eq!(record.samples, vec![sample("ab", 1.0)]);
+    }
+
+    #[test]
+    fn json_one_or_many() {
+        let record = parser(InputFormat::Json).parse(r#"{"name": "a", "value": 1}"#.to_string());
+        assert_eq!(record.samples, vec![sample("a", 1.0)]);
+        assert_eq!(record.text, "a: 1");
+
+        let record = parser(InputFormat::Json).parse(
+            r#"[{"name": "a", "value": 1}, {"name": "b", "value": 2.5, "x": 3, "unit": "V"}]"#
+                .to_string(),
+        );
+        assert_eq!(
+            record.samples,
+            vec![
+                sample("a", 1.0),
+                Sample {
+                    x: Some(3.0),
+                    unit: Some("V".to_string()),
+                    ..sample("b", 2.5)
+                }
+            ]
+        );
+        assert_eq!(record.text, "a: 1\nb: 2.5 V");
+    }
+
+    #[test]
+    fn ndjson_skips_blank_lines() {
+        let input = "{\"name\": \"a\", \"value\": 1}\n\n{\"name\": \"b\", \"value\": 2}\n";
+        let record = parser(InputFormat::Ndjson).parse(input.to_string());
+        assert_eq!(record.samples, vec![sample("a", 1.0), sample("b", 2.0)]);
+    }
+
+    #[test]
+    fn invalid_json_keeps_text() {
+        for format in [InputFormat::Json, InputFormat::Ndjson] {
+            let record = parser(format).parse("{oops".to_string());
+            assert!(record.samples.is_empty());
+            assert_eq!(record.text, "{oops");
+            assert!(record.error.is_some());
+        }
+    }
+
+    fn delimited(format: InputFormat, columns: Columns, inputs: &[&str]) -> Vec<Vec<Sample>> {
+        let mut parser = RecordParser::new(format, columns, Transforms::default());
+        inputs
+            .iter()
+            .map(|input| parser.parse(input.to_string()).samples)
+            .collect()
+    }
+
+    #[test]
+    fn csv_header_then_every_numeric_column() {
+        let records = delimited(
+            InputFormat::Csv,
+            Columns::default(),
+            &["time,cpu,gpu\n", "1, 50, 60\n2,51,n/a", "time,cpu,gpu"],
+        );
+
+        assert_eq!(
+            records,
+            vec![
+                vec![],
+                vec![
+                    sample("time", 1.0),
+                    sample("cpu", 50.0),
+                    sample("gpu", 60.0),
+                    sample("time", 2.0),
+                    sample("cpu", 51.0),
+                ],
+                // A repeated header is skipped
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_named_columns() {
+        let columns = Columns {
+            values: vec!["cpu".to_string(), "missing".to_string()],
+            label: Some("host".to_string()),
+            x: Some("time".to_string()),
+        };
+        let records = delimited(
+            InputFormat::Csv,
+            columns,
+            &["host,time,cpu,gpu", "a,1,50,60"],
+        );
+
+        assert_eq!(
+            records[1],
+            vec![Sample {
+                x: Some(1.0),
+                ..sample("a", 50.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn tsv_label_with_several_values() {
+        let columns = Columns {
+            label: Some("host".to_string()),
+            ..Default::default()
+        };
+        let records = delimited(InputFormat::Tsv, columns, &["host\tcpu\tgpu\na\t50\t60"]);
+
+        assert_eq!(
+            records[0],
+            vec![sample("a cpu", 50.0), sample("a gpu", 60.0)]
+        );
+    }
+
+    fn rules<T>(rules: &[&str]) -> Vec<Rule<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        rules
+            .iter()
+            .map(|rule| rule_from_str(rule).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rules_parse_optional_pattern() {
+        let rule = rule_from_str::<f64>(" 0.001 ").unwrap();
+        assert!(rule.pattern.is_none());
+        assert_eq!(rule.value, 0.001);
+
+        // The value follows the last =, so patterns may contain one
+        let rule = rule_from_str::<f64>("a=b*=-2").unwrap();
+        assert!(rule.pattern.unwrap().matches("a=bc"));
+        assert_eq!(rule.value, -2.0);
+
+        assert!(rule_from_str::<f64>("CPU*=fast").is_err());
+        assert!(rule_from_str::<f64>("[CPU=1").is_err());
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let scales = rules::<f64>(&["2", "CPU*=10", "GPU*=3"]);
+        assert_eq!(find(&scales, "CPU Tctl"), Some(&10.0));
+        assert_eq!(find(&scales, "GPU Edge"), Some(&3.0));
+        assert_eq!(find(&scales, "Coolant"), Some(&2.0));
+
+        // A catch-all after specific rules overrides them
+        let scales = rules::<f64>(&["CPU*=10", "2"]);
+        assert_eq!(find(&scales, "CPU Tctl"), Some(&2.0));
+
+        assert_eq!(find(&rules::<f64>(&["CPU*=10"]), "GPU Edge"), None);
+    }
+
+    #[test]
+    fn transforms_scale_then_offset_and_set_units() {
+        let transforms = Transforms {
+            scales: rules(&["0.001", "Fan*=1"]),
+            offsets: rules(&["Fan*=-100", "CPU*=-10"]),
+            units: rules(&["°C", "Fan*=RPM"]),
+            ..Default::default()
+        };
+        let mut parser = RecordParser::new(InputFormat::Json, Columns::default(), transforms);
+        let input = r#"[
+            {"name": "CPU", "value": 50000},
+            {"name": "Fan 1", "value": 1200},
+            {"name": "GPU", "value": 40000, "unit": "m°C"}
+        ]"#;
+        let record = parser.parse(input.to_string());
+
+        let unit = |unit: &str| Some(unit.to_string());
+        assert_eq!(
+            record.samples,
+            vec![
+                Sample {
+                    unit: unit("°C"),
+                    ..sample("CPU", 40.0)
+                },
+                Sample {
+                    unit: unit("RPM"),
+                    ..sample("Fan 1", 1100.0)
+                },
+                Sample {
+                    unit: unit("°C"),
+                    ..sample("GPU", 40.0)
+                },
+            ]
+        );
+
+        // Text written from samples shows them transformed
+        assert_eq!(record.text, "CPU: 40°C\nFan 1: 1100 RPM\nGPU: 40°C");
+    }
+
+    #[test]
+    fn transforms_keep_raw_text() {
+        let transforms = Transforms {
+            scales: rules(&["2"]),
+            ..Default::default()
+        };
+        let mut parser = RecordParser::new(InputFormat::Text, Columns::default(), transforms);
+        let record = parser.parse("21\tCPU".to_string());
+
+        assert_eq!(record.samples, vec![sample("CPU", 42.0)]);
+        assert_eq!(record.text, "21\tCPU");
+
+        // Unparseable input is left as it was, too
+        let transforms = Transforms {
+            scales: rules(&["2"]),
+            ..Default::default()
+        };
+        let mut parser = RecordParser::new(InputFormat::Json, Columns::default(), transforms);
+        assert_eq!(parser.parse("{oops".to_string()).text, "{oops");
+    }
+
+    #[test]
+    fn rates_from_counters() {
+        let mut transforms = Transforms {
+            rates: Some(vec![]),
+            scales: rules(&["100"]),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+        let mut apply = |samples: &[Sample], arrival| {
+            let mut samples = samples.to_vec();
+            transforms.apply(&mut samples, arrival);
+            samples
+        };
+
+        // A counter's first reading has no rate yet
+        assert_eq!(apply(&[sample("cpu", 10.0)], at(0)), vec![]);
+
+        // Rates are scaled like any other value
+        assert_eq!(
+            apply(&[sample("cpu", 30.0), sample("gpu", 5.0)], at(2)),
+            vec![sample("cpu", 1000.0)]
+        );
+
+        // A counter that went backwards was reset, so counts from zero
+        assert_eq!(
+            apply(&[sample("cpu", 4.0), sample("gpu", 9.0)], at(4)),
+            vec![sample("cpu", 200.0), sample("gpu", 200.0)]
+        );
+
+        // Readings arriving together have no interval to measure a rate over
+        assert_eq!(apply(&[sample("cpu", 5.0)], at(4)), vec![]);
+        assert_eq!(
+            apply(&[sample("cpu", 6.0)], at(5)),
+            vec![sample("cpu", 100.0)]
+        );
+    }
+
+    #[test]
+    fn rates_for_matching_counters_only() {
+        let mut transforms = Transforms {
+            rates: Some(vec!["*jiffies".parse().unwrap()]),
+            ..Default::default()
+        };
+        let start = Instant::now();
+
+        let mut samples = vec![sample("busy jiffies", 100.0), sample("load", 0.5)];
+        transforms.apply(&mut samples, start);
+        assert_eq!(samples, vec![sample("load", 0.5)]);
+
+        let mut samples = vec![sample("busy jiffies", 150.0), sample("load", 0.7)];
+        transforms.apply(&mut samples, start + std::time::Duration::from_millis(500));
+        assert_eq!(
+            samples,
+            vec![sample("busy jiffies", 100.0), sample("load", 0.7)]
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+};
+
+/// Convert text containing ANSI escape sequences into styled spans.
+/// SGR sequences are applied; any other escape sequence is discarded.
+pub fn ansi_to_text(input: &str) -> Text<'static> {
+    let mut style = Style::default();
+    let mut lines = vec![];
+    let mut spans = vec![];
+    let mut text = String::new();
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // Control sequence: parameters and intermediates, then a final byte
+                Some('[') => {
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            if c == 'm' {
+                                if !text.is_empty() {
+                                    spans.push(Span::styled(std::mem::take(&mut text), style));
+                                }
+                                style = apply_sgr(style, &params);
+                            }
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                // Operating system command: terminated by BEL or ST
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                // Character set designation
+                Some('(' | ')') => {
+                    chars.next();
+                }
+                _ => (),
+            },
+            '\n' => {
+                if !text.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut text), style));
+                }
+                lines.push(Spans::from(std::mem::take(&mut spans)));
+            }
+            '\r' => (),
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        spans.push(Span::styled(text, style));
+    }
+    if !spans.is_empty() {
+        lines.push(Spans::from(spans));
+    }
+
+    Text::from(lines)
+}
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let mut params = params
+        .split([';', ':'])
+        .map(|param| param.parse::<u16>().unwrap_or_default());
+
+    while let Some(param) = params.next() {
+        style = match param {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 => style.add_modifier(Modifier::SLOW_BLINK),
+            6 => style.add_modifier(Modifier::RAPID_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            21 | 22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(basic_color(param - 30)),
+            38 => match extended_color(&mut params) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => Style { fg: None, ..style },
+            40..=47 => style.bg(basic_color(param - 40)),
+            48 => match extended_color(&mut params) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => Style { bg: None, ..style },
+            90..=97 => style.fg(bright_color(param - 90)),
+            100..=107 => style.bg(bright_color(param - 100)),
+            _ => style,
+        }
+    }
+
+    style
+}
+
+/// Parse the remainder of a 38 / 48 sequence: 5;<INDEX> or 2;<R>;<G>;<B>
+fn extended_color(params: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match params.next()? {
+        5 => Some(Color::Indexed(params.next()? as u8)),
+        2 => Some(Color::Rgb(
+            params.next()? as u8,
+            params.next()? as u8,
+            params.next()? as u8,
+        )),
+        _ => None,
+    }
+}
+
+fn basic_color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::Gray,
+    }
+}
+
+fn bright_color(index: u16) -> Color {
+    match index {
+        0 => Color::DarkGray,
+        1 => Color::LightRed,
+        2 => Color::LightGreen,
+        3 => Color::LightYellow,
+        4 => Color::LightBlue,
+        5 => Color::LightMagenta,
+        6 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::spans;
+
+    #[test]
+    fn plain_text_keeps_lines() {
+        assert_eq!(
+            spans(ansi_to_text("a\r\nb\n\nc")),
+            vec![
+                vec![("a".to_string(), Style::default())],
+                vec![("b".to_string(), Style::default())],
+                vec![],
+                vec![("c".to_string(), Style::default())],
+            ]
+        );
+    }
+
+    #[test]
+    fn sgr_styles_carry_across_lines_until_reset() {
+        let red = Style::default().fg(Color::Red);
+        let bold_red = red.add_modifier(Modifier::BOLD);
+        assert_eq!(
+            spans(ansi_to_text("\x1b[31mhot\x1b[1m!\nstill\x1b[0m cold")),
+            vec![
+                vec![("hot".to_string(), red), ("!".to_string(), bold_red)],
+                vec![
+                    ("still".to_string(), bold_red),
+                    (" cold".to_string(), Style::default())
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn sgr_parameters() {
+        let style = |params: &str| apply_sgr(Style::default(), params);
+
+        assert_eq!(style(""), Style::default());
+        assert_eq!(
+            style("1;4;42;95"),
+            Style::default()
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                .bg(Color::Green)
+                .fg(Color::LightMagenta)
+        );
+        assert_eq!(style("38;5;208"), Style::default().fg(Color::Indexed(208)));
+        assert_eq!(
+            style("48:2:1:2:3"),
+            Style::default().bg(Color::Rgb(1, 2, 3))
+        );
+        assert_eq!(style("31;39"), Style::default());
+        assert_eq!(
+            style("1;22"),
+            Style::default().remove_modifier(Modifier::BOLD | Modifier::DIM)
+        );
+        // Incomplete extended colors are ignored
+        assert_eq!(style("38;2;1"), Style::default());
+    }
+
+    #[test]
+    fn other_sequences_are_discarded() {
+        assert_eq!(
+            spans(ansi_to_text(
+                "\x1b[2Ja\x1b]0;title\x07b\x1b]8;;url\x1b\\c\x1b(Bd"
+            )),
+            vec![vec![("abcd".to_string(), Style::default())]]
+        );
+    }
+}
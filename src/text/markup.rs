@@ -0,0 +1,170 @@
+use clap::ArgEnum;
+use tui::{
+    style::{Modifier as TuiModifier, Style},
+    text::{Span, Spans, Text},
+};
+
+use crate::style::{Color, Modifier};
+
+/// Convert text containing inline style tags into styled spans.
+///
+/// `[fg=red,bold]hot[/]` styles `hot` with a red foreground and bold text.
+/// A tag holds comma-separated `fg=<COLOR>`, `bg=<COLOR>`, modifier names,
+/// or a bare color name as shorthand for `fg`, using the same names as the CLI flags.
+/// Tags nest, `[/]` closes the innermost one, and `[[` produces a literal `[`.
+/// Anything in brackets that isn't a valid tag is displayed as-is.
+pub fn markup_to_text(input: &str) -> Text<'static> {
+    let mut styles = vec![Style::default()];
+    let mut lines = vec![];
+    let mut spans = vec![];
+    let mut text = String::new();
+
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("[[") {
+            text.push('[');
+            rest = after;
+            continue;
+        }
+
+        if let Some((tag, after)) = rest.strip_prefix('[').and_then(|tag| tag.split_once(']')) {
+            let style = *styles.last().unwrap();
+
+            // Some(None) closes the innermost tag, Some(Some(_)) opens a new one
+            let action = match tag {
+                "/" => Some(None),
+                tag => style_from_tag(tag).map(|tag_style| Some(style.patch(tag_style))),
+            };
+
+            if let Some(action) = action {
+                if !text.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut text), style));
+                }
+
+                match action {
+                    Some(style) => styles.push(style),
+                    None if styles.len() > 1 => {
+                        styles.pop();
+                    }
+                    None => (),
+                }
+
+                rest = after;
+                continue;
+            }
+        }
+
+        match c {
+            '\n' => {
+                if !text.is_empty() {
+                    spans.push(Span::styled(
+                        std::mem::take(&mut text),
+                        *styles.last().unwrap(),
+                    ));
+                }
+                lines.push(Spans::from(std::mem::take(&mut spans)));
+            }
+            '\r' => (),
+            c => text.push(c),
+        }
+
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if !text.is_empty() {
+        spans.push(Span::styled(text, *styles.last().unwrap()));
+    }
+    if !spans.is_empty() {
+        lines.push(Spans::from(spans));
+    }
+
+    Text::from(lines)
+}
+
+fn style_from_tag(tag: &str) -> Option<Style> {
+    if tag.trim().is_empty() {
+        return None;
+    }
+
+    tag.split(',')
+        .map(str::trim)
+        .try_fold(Style::default(), |style, attr| {
+            Some(if let Some(color) = attr.strip_prefix("fg=") {
+                style.fg(Color::from_str(color.trim(), true).ok()?.into())
+            } else if let Some(color) = attr.strip_prefix("bg=") {
+                style.bg(Color::from_str(color.trim(), true).ok()?.into())
+            } else if let Ok(modifier) = Modifier::from_str(attr, true) {
+                style.add_modifier(std::iter::once(modifier).sum::<TuiModifier>())
+            } else {
+                style.fg(Color::from_str(attr, true).ok()?.into())
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use tui::style::Color as TuiColor;
+
+    use super::*;
+    use crate::text::spans;
+
+    fn span(content: &str, style: Style) -> (String, Style) {
+        (content.to_string(), style)
+    }
+
+    #[test]
+    fn tags_style_until_closed() {
+        let hot = Style::default()
+            .fg(TuiColor::Red)
+            .add_modifier(TuiModifier::BOLD);
+        assert_eq!(
+            spans(markup_to_text("cpu [fg=red,bold]hot[/] ok")),
+            vec![vec![
+                span("cpu ", Style::default()),
+                span("hot", hot),
+                span(" ok", Style::default()),
+            ]]
+        );
+    }
+
+    #[test]
+    fn tags_nest_and_span_lines() {
+        let blue = Style::default().bg(TuiColor::Blue);
+        let blue_yellow = blue.fg(TuiColor::Yellow);
+        assert_eq!(
+            spans(markup_to_text("[bg=blue]a[yellow]b\nc[/]d[/]e")),
+            vec![
+                vec![span("a", blue), span("b", blue_yellow)],
+                vec![
+                    span("c", blue_yellow),
+                    span("d", blue),
+                    span("e", Style::default())
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn literal_brackets() {
+        assert_eq!(
+            spans(markup_to_text("[[red] [not a tag] [] a[b [/]")),
+            vec![vec![span("[red] [not a tag] [] a[b ", Style::default())]]
+        );
+    }
+
+    #[test]
+    fn tag_attributes() {
+        assert_eq!(style_from_tag(""), None);
+        assert_eq!(style_from_tag("fg=nope"), None);
+        assert_eq!(style_from_tag("red,nope"), None);
+        assert_eq!(
+            style_from_tag(" italic , fg=green, bg=dark-gray "),
+            Some(
+                Style::default()
+                    .add_modifier(TuiModifier::ITALIC)
+                    .fg(TuiColor::Green)
+                    .bg(TuiColor::DarkGray)
+            )
+        );
+    }
+}
@@ -0,0 +1,19 @@
+mod ansi;
+mod markup;
+
+pub use ansi::ansi_to_text;
+pub use markup::markup_to_text;
+
+/// Each line's spans, as (content, style)
+#[cfg(test)]
+fn spans(text: tui::text::Text) -> Vec<Vec<(String, tui::style::Style)>> {
+    text.lines
+        .into_iter()
+        .map(|line| {
+            line.0
+                .into_iter()
+                .map(|span| (span.content.into_owned(), span.style))
+                .collect()
+        })
+        .collect()
+}
@@ -0,0 +1,247 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    os::unix::{fs::FileTypeExt, net::UnixListener},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use clap::Args;
+use crossbeam_channel::{Receiver, Sender};
+
+use super::input::bind_unix;
+
+pub type CommandReceiver = Receiver<Command>;
+
+/// A runtime instruction for a running instance
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// scroll-x <N | +N | -N | end>
+    ScrollX(Scroll),
+    /// scroll-y <N | +N | -N | end>
+    ScrollY(Scroll),
+    /// clear
+    Clear,
+    /// title ["TITLE"]; without a title, restores the original
+    Title(Option<String>),
+    /// pause
+    Pause,
+    /// resume
+    Resume,
+    /// quit
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(command: &str) -> Result<Self, Self::Err> {
+        let command = command.trim();
+        let (name, arg) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, arg)| (name, arg.trim()))
+            .unwrap_or((command, ""));
+
+        match (name, arg) {
+            ("scroll-x", arg) => Ok(Command::ScrollX(arg.parse()?)),
+            ("scroll-y", arg) => Ok(Command::ScrollY(arg.parse()?)),
+            ("clear", "") => Ok(Command::Clear),
+            ("title", "") => Ok(Command::Title(None)),
+            ("title", title) => Ok(Command::Title(Some(unquote(title).to_string()))),
+            ("pause", "") => Ok(Command::Pause),
+            ("resume", "") => Ok(Command::Resume),
+            ("quit", "") => Ok(Command::Quit),
+            _ => Err(format!("Invalid command: {command}")),
+        }
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|quote| s.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(s)
+}
+
+/// A scroll offset, either absolute, relative to the current one, or as far as the content goes
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Scroll {
+    To(u16),
+    By(i32),
+    End,
+}
+
+impl Scroll {
+    /// Apply to an offset; views clamp the result of `End` to their content
+    pub fn apply(self, offset: u16) -> u16 {
+        match self {
+            Scroll::To(offset) => offset,
+            Scroll::By(delta) => (offset as i32 + delta).clamp(0, u16::MAX as i32) as u16,
+            Scroll::End => u16::MAX,
+        }
+    }
+}
+
+impl FromStr for Scroll {
+    type Err = String;
+
+    fn from_str(scroll: &str) -> Result<Self, Self::Err> {
+        if scroll == "end" {
+            Ok(Scroll::End)
+        } else if scroll.starts_with(['+', '-']) {
+            scroll.parse().map(Scroll::By)
+        } else {
+            scroll.parse().map(Scroll::To)
+        }
+        .map_err(|e| format!("Invalid scroll offset {scroll:?}: {e}"))
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ControlThread {
+    /// Read newline-delimited runtime commands from a named pipe, or from a socket given as unix:<PATH>.
+    /// Commands: scroll-x <N|+N|-N|end>, scroll-y <N|+N|-N|end>, clear, title ["TITLE"], pause, resume, quit
+    #[clap(long, value_name = "PATH", help_heading = "OPTIONS-CONTROL")]
+    control: Option<String>,
+}
+
+impl ControlThread {
+    /// Start reading commands, failing if the control file can't be opened or the socket bound
+    pub fn spawn(self) -> std::io::Result<CommandReceiver> {
+        let path = match self.control {
+            Some(path) => path,
+            None => return Ok(crossbeam_channel::never()),
+        };
+
+        let error = |e: std::io::Error| std::io::Error::new(e.kind(), format!("{path}: {e}"));
+        let (control_tx, control_rx) = crossbeam_channel::unbounded();
+
+        match path.strip_prefix("unix:") {
+            Some(socket) => {
+                let listener = bind_unix(Path::new(socket)).map_err(error)?;
+                std::thread::spawn(move || socket_thread(listener, control_tx));
+            }
+            None => {
+                // Opening a named pipe blocks until a writer connects, so that's left to the thread
+                let file = if std::fs::metadata(&path)
+                    .map_err(error)?
+                    .file_type()
+                    .is_fifo()
+                {
+                    None
+                } else {
+                    Some(File::open(&path).map_err(error)?)
+                };
+                std::thread::spawn(move || pipe_thread(PathBuf::from(path), file, control_tx));
+            }
+        }
+
+        Ok(control_rx)
+    }
+}
+
+/// Read commands from an opened file, or else from the named pipe at `path`,
+/// reopening it at end-of-file. The stream ends if the pipe can no longer be opened.
+fn pipe_thread(path: PathBuf, file: Option<File>, control_tx: Sender<Command>) {
+    match file {
+        Some(file) => read_commands(file, &control_tx),
+        None => {
+            while let Ok(file) = File::open(&path) {
+                read_commands(file, &control_tx);
+            }
+        }
+    }
+}
+
+fn socket_thread(listener: UnixListener, control_tx: Sender<Command>) {
+    for stream in listener.incoming().flatten() {
+        let control_tx = control_tx.clone();
+        std::thread::spawn(move || read_commands(stream, &control_tx));
+    }
+}
+
+/// Send each valid command line until end-of-file; invalid lines are skipped
+fn read_commands(reader: impl Read, control_tx: &Sender<Command>) {
+    for line in BufReader::new(reader).lines() {
+        let command = match line {
+            Ok(line) => line.parse(),
+            Err(_) => break,
+        };
+
+        if let Ok(command) = command {
+            if control_tx.send(command).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(command: &str) -> Result<Command, String> {
+        command.parse()
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(command("clear"), Ok(Command::Clear));
+        assert_eq!(command("  pause \n"), Ok(Command::Pause));
+        assert_eq!(command("resume"), Ok(Command::Resume));
+        assert_eq!(command("quit"), Ok(Command::Quit));
+        assert_eq!(command("scroll-x 5"), Ok(Command::ScrollX(Scroll::To(5))));
+        assert_eq!(
+            command("scroll-y\t-3"),
+            Ok(Command::ScrollY(Scroll::By(-3)))
+        );
+        assert_eq!(
+            command("scroll-y +10"),
+            Ok(Command::ScrollY(Scroll::By(10)))
+        );
+        assert_eq!(command("scroll-x end"), Ok(Command::ScrollX(Scroll::End)));
+    }
+
+    #[test]
+    fn titles() {
+        assert_eq!(command("title"), Ok(Command::Title(None)));
+        assert_eq!(
+            command("title CPU load"),
+            Ok(Command::Title(Some("CPU load".to_string())))
+        );
+        assert_eq!(
+            command(r#"title "  spaced  ""#),
+            Ok(Command::Title(Some("  spaced  ".to_string())))
+        );
+        assert_eq!(
+            command("title 'single'"),
+            Ok(Command::Title(Some("single".to_string())))
+        );
+        // Mismatched quotes are kept
+        assert_eq!(
+            command(r#"title "half'"#),
+            Ok(Command::Title(Some(r#""half'"#.to_string())))
+        );
+    }
+
+    #[test]
+    fn invalid_commands() {
+        assert!(command("").is_err());
+        assert!(command("jump").is_err());
+        assert!(command("clear now").is_err());
+        assert!(command("quit 1").is_err());
+        assert!(command("scroll-x").is_err());
+        assert!(command("scroll-x up").is_err());
+        assert!(command("scroll-x -70000").is_ok());
+        assert!(command("scroll-x 70000").is_err());
+    }
+
+    #[test]
+    fn scroll_apply() {
+        assert_eq!(Scroll::To(7).apply(3), 7);
+        assert_eq!(Scroll::By(-5).apply(3), 0);
+        assert_eq!(Scroll::By(5).apply(3), 8);
+        assert_eq!(Scroll::By(10).apply(u16::MAX - 2), u16::MAX);
+        assert_eq!(Scroll::End.apply(3), u16::MAX);
+    }
+}
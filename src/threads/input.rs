@@ -1,13 +1,28 @@
 use std::{
+    collections::HashSet,
     ffi::OsStr,
-    io::{BufRead, BufReader},
-    path::PathBuf,
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom},
+    net::TcpListener,
+    os::unix::{
+        fs::{FileTypeExt, MetadataExt},
+        net::UnixListener,
+    },
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
-use clap::Args;
-use crossbeam_channel::Receiver;
+use clap::{ArgEnum, Args};
+use crossbeam_channel::{Receiver, SendError, Sender};
 
-pub type InputReceiver = Receiver<String>;
+use crate::record::{Columns, InputFormat, Record, RecordParser, Transforms};
+
+pub type InputReceiver = Receiver<Record>;
+
+/// How long --follow waits at end-of-file before checking for new data
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 pub fn delimiter_from_str(delim: &str) -> std::result::Result<u8, &'static str> {
     match delim {
@@ -20,91 +35,518 @@ pub fn delimiter_from_str(delim: &str) -> std::result::Result<u8, &'static str>
     }
 }
 
-fn input_file_from_os_str(os_str: &OsStr) -> InputFile {
-    InputFile(match os_str.to_str().unwrap() {
-        "-" => None,
-        i => Some(i.into()),
-    })
-}
-
-#[derive(Debug)]
-struct InputFile(Option<PathBuf>);
-
-impl std::ops::Deref for InputFile {
-    type Target = Option<PathBuf>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
+pub fn duration_from_str(secs: &str) -> std::result::Result<Duration, String> {
+    secs.parse::<f64>()
+        .map_err(|e| e.to_string())
+        .and_then(|secs| Duration::try_from_secs_f64(secs).map_err(|e| e.to_string()))
 }
 
-impl std::ops::DerefMut for InputFile {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+fn input_source_from_os_str(os_str: &OsStr) -> InputSource {
+    match os_str.to_str().unwrap() {
+        "-" => InputSource::Stdin,
+        i => {
+            if let Some(path) = i.strip_prefix("unix:") {
+                InputSource::Unix(path.into())
+            } else if let Some(addr) = i.strip_prefix("tcp:") {
+                InputSource::Tcp(addr.into())
+            } else {
+                InputSource::File(i.into())
+            }
+        }
     }
 }
 
-impl From<Option<PathBuf>> for InputFile {
-    fn from(i: Option<PathBuf>) -> Self {
-        InputFile(i)
-    }
+#[derive(Debug, Copy, Clone, ArgEnum)]
+pub enum OnEof {
+    /// Quit the program
+    Exit,
+    /// Keep displaying the last record
+    Hold,
+    /// Open the input again and continue reading
+    Reopen,
 }
 
-impl From<InputFile> for Option<PathBuf> {
-    fn from(i: InputFile) -> Self {
-        i.0
-    }
+#[derive(Debug)]
+enum InputSource {
+    Stdin,
+    File(PathBuf),
+    /// Unix domain socket to listen on
+    Unix(PathBuf),
+    /// TCP address to listen on
+    Tcp(String),
 }
 
 #[derive(Debug, Args)]
 pub struct InputThread {
-    /// Input file; if -, read from standard input
-    #[clap(parse(from_os_str = input_file_from_os_str), default_value = "-")]
-    input: InputFile,
+    /// Input file; if -, read from standard input.
+    /// unix:<PATH> or tcp:<ADDR> listens for any number of writers instead.
+    #[clap(parse(from_os_str = input_source_from_os_str), default_value = "-")]
+    input: InputSource,
+
+    /// Keep reading at end of file, reopening it if truncated or replaced; needs an input file
+    #[clap(
+        short = 'F',
+        long,
+        conflicts_with = "exec",
+        help_heading = "OPTIONS-INPUT"
+    )]
+    follow: bool,
+
+    /// What to do on reaching the end of the input.
+    /// Defaults to reopen for named pipes and hold otherwise; standard input holds instead of reopening.
+    #[clap(
+        long,
+        arg_enum,
+        conflicts_with_all = &["follow", "exec"],
+        help_heading = "OPTIONS-INPUT"
+    )]
+    on_eof: Option<OnEof>,
+
+    /// Shell command to run repeatedly instead of reading input; each run's output is one record.
+    /// The input may only be -, which lets an output be given after it
+    #[clap(long, value_name = "COMMAND", help_heading = "OPTIONS-INPUT")]
+    exec: Option<String>,
+
+    /// Seconds between the start of successive --exec runs, or between reopening a regular file
+    #[clap(long, parse(try_from_str = duration_from_str), default_value = "1.0", value_name = "SECONDS", help_heading = "OPTIONS-INPUT")]
+    interval: Duration,
 
     /// Input delimiter
     #[clap(short, long, parse(try_from_str = delimiter_from_str), default_value = "\0", help_heading = "OPTIONS-INPUT")]
     delimiter: u8,
+
+    /// Input record format
+    #[clap(long, arg_enum, default_value = "text", help_heading = "OPTIONS-INPUT")]
+    input_format: InputFormat,
+
+    #[clap(flatten)]
+    columns: Columns,
+
+    #[clap(flatten)]
+    transforms: Transforms,
 }
 
 impl InputThread {
+    /// Check combinations of options that depend on the kind of input, which clap can't express
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let listener = matches!(self.input, InputSource::Unix(_) | InputSource::Tcp(_));
+
+        if self.exec.is_some() && !matches!(self.input, InputSource::Stdin) {
+            Err("--exec can't be used with an input; pass - as the input to give an output")
+        } else if self.follow && !matches!(self.input, InputSource::File(_)) {
+            Err("--follow can only be used with an input file")
+        } else if self.on_eof.is_some() && listener {
+            Err("--on-eof can't be used with a listener, which keeps accepting writers")
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn spawn(self) -> InputReceiver {
         let InputThread {
-            input: file,
+            input,
+            follow,
+            on_eof,
+            exec,
+            interval,
             delimiter,
+            input_format,
+            columns,
+            transforms,
         } = self;
 
-        let (stdin_tx, stdin_rx) = crossbeam_channel::unbounded::<String>();
+        let parser = RecordParser::new(input_format, columns, transforms);
+
+        let (stdin_tx, stdin_rx) = crossbeam_channel::unbounded::<Record>();
+
+        match (exec, input) {
+            (Some(command), _) => std::thread::spawn(move || {
+                exec_thread(command, interval, delimiter, parser, stdin_tx)
+            }),
+            (None, InputSource::File(path)) if follow => {
+                std::thread::spawn(move || follow_thread(path, delimiter, parser, stdin_tx))
+            }
+            (None, InputSource::File(path)) => std::thread::spawn(move || {
+                read_thread(Some(path), on_eof, interval, delimiter, parser, stdin_tx)
+            }),
+            (None, InputSource::Stdin) => std::thread::spawn(move || {
+                read_thread(None, on_eof, interval, delimiter, parser, stdin_tx)
+            }),
+            (None, InputSource::Unix(path)) => {
+                std::thread::spawn(move || unix_thread(path, delimiter, parser, stdin_tx))
+            }
+            (None, InputSource::Tcp(addr)) => {
+                std::thread::spawn(move || tcp_thread(addr, delimiter, parser, stdin_tx))
+            }
+        };
+
+        stdin_rx
+    }
+}
+
+fn read_thread(
+    file: Option<PathBuf>,
+    on_eof: Option<OnEof>,
+    interval: Duration,
+    delimiter: u8,
+    mut parser: RecordParser,
+    stdin_tx: Sender<Record>,
+) {
+    let is_fifo = file
+        .as_ref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.file_type().is_fifo())
+        .unwrap_or_default();
+
+    // Named pipes are reopened by default so a restarted writer gets picked up
+    let on_eof = on_eof.unwrap_or(if is_fifo { OnEof::Reopen } else { OnEof::Hold });
+
+    // Where a regular file was last read up to, so reopening it doesn't send the same records again
+    let mut left_at = None;
+
+    loop {
+        let read = if let Some(path) = &file {
+            match File::open(path).and_then(|file| resume(file, left_at)) {
+                Ok(mut reader) => {
+                    let read = read_records(&mut reader, delimiter, &mut parser, &stdin_tx, None);
+                    left_at = ReadPosition::of(&mut reader);
+                    read
+                }
+                Err(e) => stdin_tx
+                    .send(Record::error(format!("{}: {e}", path.display())))
+                    .map(drop),
+            }
+        } else {
+            read_records(
+                std::io::stdin().lock(),
+                delimiter,
+                &mut parser,
+                &stdin_tx,
+                None,
+            )
+        };
+
+        if read.is_err() {
+            break;
+        }
+
+        match on_eof {
+            OnEof::Exit => break,
+            // Opening a FIFO blocks until a writer connects, but a regular file would spin
+            OnEof::Reopen if file.is_some() => {
+                if !is_fifo {
+                    std::thread::sleep(interval);
+                }
+            }
+            OnEof::Reopen | OnEof::Hold => hold(stdin_tx),
+        }
+    }
+}
+
+/// A file and the offset reached in it
+#[derive(Debug, Copy, Clone)]
+struct ReadPosition {
+    dev: u64,
+    ino: u64,
+    offset: u64,
+}
+
+impl ReadPosition {
+    /// Where `reader` has read up to, or None if it can't seek, as with a named pipe
+    fn of(reader: &mut BufReader<File>) -> Option<Self> {
+        let metadata = reader.get_ref().metadata().ok()?;
+        Some(ReadPosition {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+            offset: reader.stream_position().ok()?,
+        })
+    }
+}
+
+/// Continue reading `file` from where it was `left_at`.
+/// Starts from the beginning if it's been replaced or truncated since.
+fn resume(file: File, left_at: Option<ReadPosition>) -> std::io::Result<BufReader<File>> {
+    let metadata = file.metadata()?;
+    let mut reader = BufReader::new(file);
+
+    if let Some(left_at) = left_at {
+        if metadata.is_file()
+            && metadata.dev() == left_at.dev
+            && metadata.ino() == left_at.ino
+            && metadata.len() >= left_at.offset
+        {
+            reader.seek(SeekFrom::Start(left_at.offset))?;
+        }
+    }
+
+    Ok(reader)
+}
+
+/// Send each delimited record from `reader` until it reaches end-of-file.
+/// A read error is sent as a record and ends reading, as end-of-file would.
+/// Fails if the receiving end of the channel has gone away.
+fn read_records(
+    mut reader: impl BufRead,
+    delimiter: u8,
+    parser: &mut RecordParser,
+    stdin_tx: &Sender<Record>,
+    source: Option<&str>,
+) -> Result<(), SendError<Record>> {
+    loop {
+        let mut buf = vec![];
+
+        let (record, more) = match reader.read_until(delimiter, &mut buf) {
+            Ok(0) => break Ok(()),
+            Ok(_) => {
+                // Remove trailing delimiter
+                if buf.last() == Some(&delimiter) {
+                    buf.pop();
+                }
+
+                (
+                    parser.parse(String::from_utf8_lossy(&buf).into_owned()),
+                    true,
+                )
+            }
+            // Retrying would most likely fail the same way
+            Err(e) => (Record::error(e), false),
+        };
+
+        stdin_tx.send(Record {
+            source: source.map(ToString::to_string),
+            ..record
+        })?;
+
+        if !more {
+            break Ok(());
+        }
+    }
+}
+
+/// Stop reading while keeping the channel open, so the last record stays on screen
+fn hold(_stdin_tx: Sender<Record>) -> ! {
+    loop {
+        std::thread::park();
+    }
+}
+
+/// Listen on a Unix domain socket, clearing out one left behind by a previous run
+pub(crate) fn bind_unix(path: &Path) -> std::io::Result<UnixListener> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    UnixListener::bind(path)
+}
+
+fn unix_thread(path: PathBuf, delimiter: u8, parser: RecordParser, stdin_tx: Sender<Record>) {
+    match bind_unix(&path) {
+        Ok(listener) => listen(
+            listener
+                .incoming()
+                .map(|stream| stream.map(|stream| (stream, "unix".to_string()))),
+            delimiter,
+            parser,
+            stdin_tx,
+        ),
+        Err(e) => {
+            let _ = stdin_tx.send(Record::error(format!("{}: {e}", path.display())));
+            hold(stdin_tx)
+        }
+    }
+}
+
+fn tcp_thread(addr: String, delimiter: u8, parser: RecordParser, stdin_tx: Sender<Record>) {
+    match TcpListener::bind(&addr) {
+        Ok(listener) => listen(
+            listener.incoming().map(|stream| {
+                let stream = stream?;
+                let peer = stream.peer_addr()?.ip().to_string();
+                Ok((stream, peer))
+            }),
+            delimiter,
+            parser,
+            stdin_tx,
+        ),
+        Err(e) => {
+            let _ = stdin_tx.send(Record::error(format!("{addr}: {e}")));
+            hold(stdin_tx)
+        }
+    }
+}
+
+/// Read records from each accepted connection on its own thread, tagged with the connection's source.
+/// Sources are the peer numbered as <PEER>#<N>, with the lowest number not taken by another open
+/// connection, so concurrent writers are kept apart while one that reconnects keeps its series.
+fn listen<S: Read + Send + 'static>(
+    connections: impl Iterator<Item = std::io::Result<(S, String)>>,
+    delimiter: u8,
+    parser: RecordParser,
+    stdin_tx: Sender<Record>,
+) {
+    let open = Arc::new(Mutex::new(HashSet::new()));
+
+    for (stream, peer) in connections.flatten() {
+        let mut parser = parser.clone();
+        let stdin_tx = stdin_tx.clone();
+        let open = open.clone();
+
+        let source = {
+            let mut open = open.lock().unwrap();
+            let source = (1..)
+                .map(|n| format!("{peer}#{n}"))
+                .find(|source| !open.contains(source))
+                .unwrap();
+            open.insert(source.clone());
+            source
+        };
 
         std::thread::spawn(move || {
-            let mut file = file
-                .as_ref()
-                .map(|path| std::fs::File::open(path).expect("Failed to open input file"))
-                .map(BufReader::new);
+            let stream = BufReader::new(stream);
+            let _ = read_records(stream, delimiter, &mut parser, &stdin_tx, Some(&source));
+            open.lock().unwrap().remove(&source);
+        });
+    }
+}
 
-            loop {
-                let mut buf = vec![];
+/// Read records as they're appended to the file at `path`.
+/// An I/O error is sent as a record, after which the last records stay on screen.
+fn follow_thread(path: PathBuf, delimiter: u8, mut parser: RecordParser, stdin_tx: Sender<Record>) {
+    let error = |e: std::io::Error| Record::error(format!("{}: {e}", path.display()));
 
-                let input = if let Some(file) = &mut file {
-                    file.read_until(delimiter, &mut buf)
-                } else {
-                    std::io::stdin().lock().read_until(delimiter, &mut buf)
-                };
+    let mut file = match open_when_present(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            let _ = stdin_tx.send(error(e));
+            hold(stdin_tx)
+        }
+    };
+    let mut buf = vec![];
 
+    loop {
+        match file.read_until(delimiter, &mut buf) {
+            Ok(_) if buf.last() == Some(&delimiter) => {
                 // Remove trailing delimiter
                 buf.pop();
 
-                match input {
-                    Ok(count) => {
-                        if count > 0 {
-                            stdin_tx.send(String::from_utf8(buf).unwrap()).unwrap()
+                let input = String::from_utf8_lossy(&buf).into_owned();
+                buf.clear();
+                if stdin_tx.send(parser.parse(input)).is_err() {
+                    break;
+                }
+            }
+            // At end of file, any partial record is kept until its delimiter arrives
+            Ok(_) => {
+                std::thread::sleep(FOLLOW_POLL_INTERVAL);
+
+                if replaced_or_truncated(&path, &mut file) {
+                    if !buf.is_empty() {
+                        let input = String::from_utf8_lossy(&buf).into_owned();
+                        buf.clear();
+                        if stdin_tx.send(parser.parse(input)).is_err() {
+                            break;
                         }
                     }
-                    Err(e) => panic!("{e:}"),
+
+                    file = match open_when_present(&path) {
+                        Ok(file) => file,
+                        Err(e) => {
+                            let _ = stdin_tx.send(error(e));
+                            hold(stdin_tx)
+                        }
+                    };
                 }
             }
-        });
+            Err(e) => {
+                let _ = stdin_tx.send(error(e));
+                hold(stdin_tx)
+            }
+        }
+    }
+}
 
-        stdin_rx
+/// Open the file at `path`, waiting for it to be created if it doesn't exist yet
+fn open_when_present(path: &Path) -> std::io::Result<BufReader<File>> {
+    loop {
+        match File::open(path) {
+            Ok(file) => break Ok(BufReader::new(file)),
+            Err(e) if e.kind() == ErrorKind::NotFound => std::thread::sleep(FOLLOW_POLL_INTERVAL),
+            Err(e) => break Err(e),
+        }
+    }
+}
+
+/// Whether the file at `path` is no longer the one being read, or has shrunk below the read position.
+/// A missing path counts as unchanged, so reading resumes once a replacement appears.
+fn replaced_or_truncated(path: &Path, file: &mut BufReader<File>) -> bool {
+    let current = match std::fs::metadata(path) {
+        Ok(current) => current,
+        Err(_) => return false,
+    };
+
+    let opened = match file.get_ref().metadata() {
+        Ok(opened) => opened,
+        Err(_) => return true,
+    };
+
+    let position = file.stream_position().unwrap_or_default();
+
+    current.dev() != opened.dev() || current.ino() != opened.ino() || opened.len() < position
+}
+
+fn exec_thread(
+    command: String,
+    interval: Duration,
+    delimiter: u8,
+    mut parser: RecordParser,
+    stdin_tx: Sender<Record>,
+) {
+    let mut next = Instant::now();
+
+    loop {
+        let record = match Command::new("sh").arg("-c").arg(&command).output() {
+            Ok(output) => {
+                let mut stdout = output.stdout;
+
+                // Remove trailing delimiter
+                if stdout.last() == Some(&delimiter) {
+                    stdout.pop();
+                }
+
+                let record = parser.parse(String::from_utf8_lossy(&stdout).into_owned());
+                Record {
+                    error: exec_error(output.status, &output.stderr).or(record.error),
+                    ..record
+                }
+            }
+            Err(e) => Record::error(format!("{command}: {e}")),
+        };
+
+        if stdin_tx.send(record).is_err() {
+            break;
+        }
+
+        // Schedule runs from their start time so command duration doesn't skew the interval
+        next += interval;
+        let now = Instant::now();
+        if next > now {
+            std::thread::sleep(next - now);
+        } else {
+            next = now;
+        }
+    }
+}
+
+fn exec_error(status: ExitStatus, stderr: &[u8]) -> Option<String> {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stderr = stderr.trim_end();
+
+    match (status.success(), stderr.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(stderr.to_string()),
+        (false, true) => Some(status.to_string()),
+        (false, false) => Some(format!("{status}\n{stderr}")),
     }
 }
@@ -1,39 +1,69 @@
 use std::{cell::RefCell, rc::Rc};
 
 use crossterm::{
-    event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use tui::backend::CrosstermBackend;
 
-use super::{event::CrosstermEventReceiver, input::InputReceiver};
-use crate::{backend::Backend, Result};
+use super::{
+    control::{Command, CommandReceiver},
+    event::CrosstermEventReceiver,
+    input::InputReceiver,
+};
+use crate::{
+    backend::Backend,
+    keymap::{self, Bindings},
+    record::Record,
+    Result,
+};
 
 pub type Terminal = tui::Terminal<CrosstermBackend<Backend>>;
 pub type Frame<'a> = tui::Frame<'a, CrosstermBackend<Backend>>;
 
-pub type InputBuffer = Rc<RefCell<String>>;
+pub type InputBuffer = Rc<RefCell<Record>>;
+
+/// A widget driven by the main thread
+pub trait View {
+    fn draw(&mut self, f: &mut Frame);
 
-pub trait FnDraw: FnMut(&mut Frame) {}
-impl<T> FnDraw for T where T: FnMut(&mut Frame) {}
+    /// Apply a runtime command; commands that don't concern the view are ignored
+    fn command(&mut self, _command: &Command) {}
+
+    /// Observe a record as it's displayed; called exactly once per record received
+    fn record(&mut self, _record: &Record) {}
+
+    /// Handle a mouse event; only delivered when mouse capture is enabled
+    fn mouse(&mut self, _event: &MouseEvent) {}
+}
 
 pub fn main_thread(
     terminal: &mut Terminal,
     stdin_rx: InputReceiver,
     event_rx: CrosstermEventReceiver,
+    control_rx: CommandReceiver,
+    bindings: Bindings,
     buf: InputBuffer,
-    draw: impl FnDraw,
+    view: impl View,
 ) -> Result {
-    main_initialize(terminal)?;
-    main_loop(terminal, stdin_rx, event_rx, buf, draw)?;
-    main_finalize(terminal)?;
+    let mouse = bindings.mouse;
+    main_initialize(terminal, mouse)?;
+    main_loop(
+        terminal, stdin_rx, event_rx, control_rx, bindings, buf, view,
+    )?;
+    main_finalize(terminal, mouse)?;
     Ok(())
 }
 
-fn main_initialize(terminal: &mut Terminal) -> Result {
+fn main_initialize(terminal: &mut Terminal, mouse: bool) -> Result {
     terminal.hide_cursor()?;
     execute!(terminal.backend_mut(), EnterAlternateScreen,)?;
+    if mouse {
+        execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    }
     enable_raw_mode()?;
     Ok(())
 }
@@ -42,33 +72,83 @@ fn main_loop(
     terminal: &mut Terminal,
     stdin_rx: InputReceiver,
     event_rx: CrosstermEventReceiver,
+    mut control_rx: CommandReceiver,
+    bindings: Bindings,
     buf: InputBuffer,
-    mut draw: impl FnDraw,
+    mut view: impl View,
 ) -> Result {
+    // While paused, records are held back until resumed
+    let mut paused: Option<Vec<Record>> = None;
+
     loop {
-        terminal.draw(|f| draw(f))?;
+        terminal.draw(|f| view.draw(f))?;
 
-        crossbeam_channel::select! {
+        // Key bindings and the control stream both resolve to commands
+        let command = crossbeam_channel::select! {
             recv(stdin_rx) -> msg => {
-                *buf.borrow_mut() = msg.unwrap();
+                // Input threads only hang up once there's nothing left to show
+                match msg {
+                    Ok(record) => match &mut paused {
+                        Some(pending) => pending.push(record),
+                        None => display(&buf, &mut view, record),
+                    },
+                    Err(_) => break Ok(()),
+                }
+                continue;
             },
-            recv(event_rx) -> msg => {
-                match msg.unwrap() {
-                    Event::Key(KeyEvent {
-                        code: KeyCode::Char('c'),
-                        modifiers: KeyModifiers::CONTROL
-                    }) => {
-                        break Ok(())
-                    }
-                    _ => ()
+            recv(event_rx) -> msg => match msg.unwrap() {
+                Event::Key(KeyEvent {
+                    code: KeyCode::Char('c'),
+                    modifiers: KeyModifiers::CONTROL,
+                }) => break Ok(()),
+                Event::Key(key) => match bindings.keys.get(&keymap::normalize(key)) {
+                    Some(command) => command.clone(),
+                    None => continue,
+                },
+                Event::Mouse(event) => {
+                    view.mouse(&event);
+                    continue;
+                }
+                _ => continue,
+            },
+            recv(control_rx) -> msg => match msg {
+                Ok(command) => command,
+                Err(_) => {
+                    control_rx = crossbeam_channel::never();
+                    continue;
                 }
             },
         };
+
+        match command {
+            Command::Quit => break Ok(()),
+            Command::Pause => {
+                paused.get_or_insert_with(Vec::new);
+            }
+            Command::Resume => {
+                for record in paused.take().into_iter().flatten() {
+                    display(&buf, &mut view, record);
+                }
+            }
+            Command::Clear => *buf.borrow_mut() = Default::default(),
+            _ => (),
+        }
+
+        view.command(&command);
     }
 }
 
-fn main_finalize(terminal: &mut Terminal) -> Result {
+/// Hand a record to the view, then make it the current one
+fn display(buf: &InputBuffer, view: &mut impl View, record: Record) {
+    view.record(&record);
+    *buf.borrow_mut() = record;
+}
+
+fn main_finalize(terminal: &mut Terminal, mouse: bool) -> Result {
     disable_raw_mode()?;
+    if mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
     execute!(terminal.backend_mut(), LeaveAlternateScreen,)?;
     terminal.show_cursor()?;
     Ok(())
@@ -1,3 +1,4 @@
 pub mod main;
 pub mod input;
 pub mod event;
+pub mod control;
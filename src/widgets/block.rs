@@ -56,6 +56,16 @@ pub struct Block {
     pub border: BlockBorder,
 }
 
+impl Block {
+    /// A copy of this block, with its title replaced if `title` is set
+    pub fn with_title(&self, title: &Option<String>) -> Block {
+        Block {
+            title: title.clone().or_else(|| self.title.clone()),
+            ..self.clone()
+        }
+    }
+}
+
 impl TryFrom<Block> for TuiBlock<'_> {
     type Error = ();
 
@@ -0,0 +1,53 @@
+use tui::{buffer::Buffer, layout::Rect, style::Style, symbols::bar::NINE_LEVELS, widgets::Widget};
+
+/// Vertical bars rising from the bottom of the graph area to each point
+pub struct Bars<'a> {
+    pub data: &'a [(f64, f64)],
+    pub x_bounds: [f64; 2],
+    pub y_bounds: [f64; 2],
+    pub style: Style,
+}
+
+impl Widget for Bars<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [x_min, x_max] = self.x_bounds;
+        let [y_min, y_max] = self.y_bounds;
+        if area.width == 0 || area.height == 0 || x_max <= x_min || y_max <= y_min {
+            return;
+        }
+
+        let levels = [
+            NINE_LEVELS.one_eighth,
+            NINE_LEVELS.one_quarter,
+            NINE_LEVELS.three_eighths,
+            NINE_LEVELS.half,
+            NINE_LEVELS.five_eighths,
+            NINE_LEVELS.three_quarters,
+            NINE_LEVELS.seven_eighths,
+            NINE_LEVELS.full,
+        ];
+
+        for (x, y) in self.data {
+            if !(x_min..=x_max).contains(x) {
+                continue;
+            }
+
+            let column = area.left()
+                + ((x - x_min) / (x_max - x_min) * (area.width - 1) as f64).round() as u16;
+            let fraction = ((y - y_min) / (y_max - y_min)).clamp(0.0, 1.0);
+            let mut eighths = (fraction * area.height as f64 * 8.0).round() as usize;
+
+            for row in (area.top()..area.bottom()).rev() {
+                if eighths == 0 {
+                    break;
+                }
+
+                let level = eighths.min(8);
+                buf.get_mut(column, row)
+                    .set_symbol(levels[level - 1])
+                    .set_style(self.style);
+                eighths -= level;
+            }
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
+use crate::record::Record;
+
+/// Series are keyed by source as well as name, so concurrent writers are kept apart
+pub type SeriesKey = (Option<String>, String);
+
+/// A named run of points
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub key: SeriesKey,
+    /// Place among the series, which picks its color and stays with it until it ages out
+    pub slot: usize,
+    pub points: Vec<(f64, f64)>,
+    /// Unit of the latest sample
+    pub unit: Option<String>,
+    /// Whether samples carry their own X coordinates, rather than being placed by arrival
+    explicit_x: bool,
+    /// Tick of the latest record with a sample for this series
+    updated: u64,
+}
+
+impl Series {
+    pub fn name(&self) -> &str {
+        &self.key.1
+    }
+}
+
+/// The latest samples of every series received so far; updated once per record, independently of drawing
+#[derive(Debug, Default, Clone)]
+pub struct History {
+    /// Series with retained samples, in slot order
+    series: Vec<Series>,
+    /// Records received from every source, used as the X coordinate of their samples
+    tick: u64,
+    /// When each retained record arrived, ending at `tick - 1`
+    arrivals: VecDeque<SystemTime>,
+    /// Records retained, and so samples retained per series
+    capacity: usize,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            capacity: capacity.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Append a record's samples to their series.
+    /// Samples from more than `capacity` records ago are dropped, as are series with no samples left,
+    /// whichever source they came from.
+    pub fn push(&mut self, record: &Record) {
+        if record.samples.is_empty() {
+            return;
+        }
+
+        let tick = self.tick;
+        self.tick += 1;
+        self.arrivals
+            .push_back(record.time.unwrap_or_else(SystemTime::now));
+        if self.arrivals.len() > self.capacity {
+            self.arrivals.pop_front();
+        }
+
+        for sample in &record.samples {
+            let key = (record.source.clone(), sample.name.clone());
+            let series = match self.series.iter().position(|series| series.key == key) {
+                Some(i) => &mut self.series[i],
+                None => {
+                    // Series are in slot order, so the first gap is the lowest slot freed by
+                    // a series that aged out
+                    let slot = self
+                        .series
+                        .iter()
+                        .enumerate()
+                        .position(|(i, series)| series.slot != i)
+                        .unwrap_or(self.series.len());
+                    self.series.insert(
+                        slot,
+                        Series {
+                            key,
+                            slot,
+                            points: vec![],
+                            unit: None,
+                            explicit_x: false,
+                            updated: tick,
+                        },
+                    );
+                    &mut self.series[slot]
+                }
+            };
+
+            series.explicit_x |= sample.x.is_some();
+            series.updated = tick;
+            series.unit.clone_from(&sample.unit);
+            series
+                .points
+                .push((sample.x.unwrap_or(tick as f64), sample.value));
+
+            let excess = series.points.len().saturating_sub(self.capacity);
+            series.points.drain(..excess);
+        }
+
+        // Series missing from recent records, such as those of a closed connection,
+        // age out like any other sample
+        let oldest = (tick + 1).saturating_sub(self.capacity as u64);
+        for series in &mut self.series {
+            if series.explicit_x {
+                if series.updated < oldest {
+                    series.points.clear();
+                }
+            } else {
+                let expired = series.points.partition_point(|(x, _)| *x < oldest as f64);
+                series.points.drain(..expired);
+            }
+        }
+        self.series.retain(|series| !series.points.is_empty());
+    }
+
+    pub fn clear(&mut self) {
+        self.series.clear();
+        self.tick = 0;
+        self.arrivals.clear();
+    }
+
+    /// A series' name for display, along with its source if series come from more than one
+    pub fn label(&self, series: &Series) -> String {
+        let mut sources = self.series.iter().map(|series| &series.key.0);
+        let first = sources.next();
+        match &series.key.0 {
+            Some(source) if sources.any(|other| Some(other) != first) => {
+                format!("{} [{source}]", series.name())
+            }
+            _ => series.name().to_string(),
+        }
+    }
+
+    /// X coordinate of the oldest record retained, whether or not any series still has a sample from it
+    pub fn start(&self) -> u64 {
+        self.tick.saturating_sub(self.capacity as u64)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Series> + Clone {
+        self.series.iter()
+    }
+
+    /// The unit every series shares, if any
+    pub fn unit(&self) -> Option<&str> {
+        let mut units = self.series.iter().map(|series| series.unit.as_deref());
+        let unit = units.next()??;
+        units.all(|other| other == Some(unit)).then_some(unit)
+    }
+
+    /// Smallest and largest X coordinates of samples that carried their own,
+    /// or None if samples are placed by arrival
+    pub fn explicit_x_range(&self) -> Option<[f64; 2]> {
+        self.series
+            .iter()
+            .filter(|series| series.explicit_x)
+            .flat_map(|series| &series.points)
+            .fold(None, |range, (x, _)| match range {
+                Some([min, max]) => Some([x.min(min), x.max(max)]),
+                None => Some([*x, *x]),
+            })
+    }
+
+    /// X coordinate of the oldest retained sample
+    pub fn oldest(&self) -> Option<f64> {
+        self.points_x(<[_]>::first).reduce(f64::min)
+    }
+
+    /// X coordinate of the latest sample
+    pub fn latest(&self) -> Option<f64> {
+        self.points_x(<[_]>::last).reduce(f64::max)
+    }
+
+    /// When the sample at X coordinate `x` arrived, extrapolating from the average interval
+    /// between arrivals
+    pub fn time_at(&self, x: f64) -> Option<SystemTime> {
+        let first = *self.arrivals.front()?;
+        let last = *self.arrivals.back()?;
+
+        let interval = match self.arrivals.len() {
+            1 => Duration::ZERO,
+            n => last.duration_since(first).unwrap_or_default() / (n - 1) as u32,
+        };
+
+        let offset = x - (self.tick - 1) as f64;
+        let shift = interval.mul_f64(offset.abs());
+        if offset < 0.0 {
+            last.checked_sub(shift)
+        } else {
+            last.checked_add(shift)
+        }
+    }
+
+    fn points_x<'a>(
+        &'a self,
+        select: impl Fn(&'a [(f64, f64)]) -> Option<&'a (f64, f64)> + 'a,
+    ) -> impl Iterator<Item = f64> + 'a {
+        self.series
+            .iter()
+            .filter_map(move |series| select(&series.points))
+            .map(|(x, _)| *x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::Sample;
+
+    fn record(source: Option<&str>, samples: &[(&str, f64)]) -> Record {
+        Record {
+            samples: samples
+                .iter()
+                .map(|(name, value)| Sample {
+                    name: name.to_string(),
+                    value: *value,
+                    x: None,
+                    unit: None,
+                })
+                .collect(),
+            source: source.map(ToString::to_string),
+            ..Default::default()
+        }
+    }
+
+    type Points = Vec<(f64, f64)>;
+
+    /// Each series as (name, slot, points)
+    fn series(history: &History) -> Vec<(&str, usize, Points)> {
+        history
+            .iter()
+            .map(|series| (series.name(), series.slot, series.points.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn samples_keyed_by_name() {
+        let mut history = History::new(10);
+        history.push(&record(None, &[("a", 1.0), ("b", 2.0)]));
+        history.push(&record(None, &[("b", 3.0), ("a", 4.0)]));
+        // Records without samples, such as errors, don't take up a tick
+        history.push(&Record::error("oops"));
+        history.push(&record(None, &[("c", 5.0), ("a", 6.0)]));
+
+        assert_eq!(
+            series(&history),
+            vec![
+                ("a", 0, vec![(0.0, 1.0), (1.0, 4.0), (2.0, 6.0)]),
+                ("b", 1, vec![(0.0, 2.0), (1.0, 3.0)]),
+                ("c", 2, vec![(2.0, 5.0)]),
+            ]
+        );
+        assert_eq!(history.oldest(), Some(0.0));
+        assert_eq!(history.latest(), Some(2.0));
+    }
+
+    #[test]
+    fn capacity_trims_oldest_records() {
+        let mut history = History::new(3);
+        for i in 0..5 {
+            history.push(&record(None, &[("a", i as f64)]));
+        }
+
+        assert_eq!(
+            series(&history),
+            vec![("a", 0, vec![(2.0, 2.0), (3.0, 3.0), (4.0, 4.0)])]
+        );
+        assert_eq!(history.oldest(), Some(2.0));
+        assert_eq!(history.latest(), Some(4.0));
+    }
+
+    #[test]
+    fn missing_series_age_out() {
+        let mut history = History::new(3);
+        history.push(&record(None, &[("a", 1.0), ("b", 2.0)]));
+        history.push(&record(None, &[("a", 3.0)]));
+        history.push(&record(None, &[("a", 5.0)]));
+        assert_eq!(history.iter().count(), 2);
+
+        history.push(&record(None, &[("a", 7.0)]));
+        assert_eq!(
+            series(&history),
+            vec![("a", 0, vec![(1.0, 3.0), (2.0, 5.0), (3.0, 7.0)])]
+        );
+
+        // The slot of a series that aged out goes to the next new one
+        history.push(&record(None, &[("c", 9.0), ("a", 8.0)]));
+        assert_eq!(series(&history)[1], ("c", 1, vec![(4.0, 9.0)]));
+        history.push(&record(None, &[("b", 10.0)]));
+        assert_eq!(series(&history)[2], ("b", 2, vec![(5.0, 10.0)]));
+    }
+
+    #[test]
+    fn explicit_x_series_age_out_when_stale() {
+        let mut history = History::new(2);
+        let mut xy = record(None, &[("xy", 1.0)]);
+        xy.samples[0].x = Some(100.0);
+        history.push(&xy);
+        assert_eq!(history.explicit_x_range(), Some([100.0, 100.0]));
+
+        history.push(&record(None, &[("a", 1.0)]));
+        assert_eq!(history.iter().count(), 2);
+
+        history.push(&record(None, &[("a", 2.0)]));
+        assert_eq!(
+            series(&history),
+            vec![("a", 1, vec![(1.0, 1.0), (2.0, 2.0)])]
+        );
+        assert_eq!(history.explicit_x_range(), None);
+    }
+
+    #[test]
+    fn sources_share_one_clock() {
+        let mut history = History::new(10);
+        history.push(&record(Some("unix#1"), &[("cpu", 1.0)]));
+        history.push(&record(Some("unix#1"), &[("cpu", 2.0)]));
+        history.push(&record(Some("unix#2"), &[("cpu", 3.0)]));
+
+        let keys = history
+            .iter()
+            .map(|series| (series.key.clone(), series.points.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                (
+                    (Some("unix#1".to_string()), "cpu".to_string()),
+                    vec![(0.0, 1.0), (1.0, 2.0)]
+                ),
+                (
+                    (Some("unix#2".to_string()), "cpu".to_string()),
+                    vec![(2.0, 3.0)]
+                ),
+            ]
+        );
+
+        // Series are told apart by source only while there's more than one
+        let labels = history
+            .iter()
+            .map(|series| history.label(series))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["cpu [unix#1]", "cpu [unix#2]"]);
+
+        let mut history = History::new(10);
+        history.push(&record(Some("unix#1"), &[("cpu", 1.0)]));
+        let series = history.iter().next().unwrap();
+        assert_eq!(history.label(series), "cpu");
+    }
+
+    #[test]
+    fn clear_starts_over() {
+        let mut history = History::new(10);
+        history.push(&record(None, &[("a", 1.0), ("b", 2.0)]));
+        history.clear();
+        history.push(&record(None, &[("b", 3.0)]));
+
+        assert_eq!(series(&history), vec![("b", 0, vec![(0.0, 3.0)])]);
+    }
+}
@@ -0,0 +1,129 @@
+use std::{str::FromStr, time::SystemTime};
+
+use chrono::{DateTime, Local};
+
+/// How axis tick values are written
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LabelFormat {
+    /// Decimal places chosen from the spacing between ticks
+    Auto,
+    /// A fixed number of decimal places
+    Fixed(usize),
+    /// Three significant digits with an SI suffix, such as 2.4k
+    Si,
+    /// Scientific notation
+    Scientific,
+    /// Time relative to now, such as -30s; X axis only
+    Relative,
+    /// Local wall-clock time; X axis only
+    Clock,
+}
+
+impl FromStr for LabelFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "auto" => Ok(LabelFormat::Auto),
+            "si" => Ok(LabelFormat::Si),
+            "e" => Ok(LabelFormat::Scientific),
+            "relative" => Ok(LabelFormat::Relative),
+            "clock" => Ok(LabelFormat::Clock),
+            format => format
+                .strip_prefix('.')
+                .and_then(|places| places.parse().ok())
+                .map(LabelFormat::Fixed)
+                .ok_or_else(|| format!("Invalid label format: {format}")),
+        }
+    }
+}
+
+impl LabelFormat {
+    /// `count` evenly spaced labels spanning `bounds`.
+    /// `time_at` maps a value to the time it was received, for time formats.
+    pub fn labels(
+        self,
+        bounds: [f64; 2],
+        count: usize,
+        time_at: impl Fn(f64) -> Option<SystemTime>,
+    ) -> Vec<String> {
+        let [min, max] = bounds;
+        let step = (max - min) / count.saturating_sub(1).max(1) as f64;
+
+        let now = SystemTime::now();
+
+        // Time formats fall back to plain numbers until there are arrivals to go by
+        (0..count)
+            .map(|i| min + step * i as f64)
+            .map(|value| match self {
+                LabelFormat::Relative => time_at(value)
+                    .map(|time| relative(time, now))
+                    .unwrap_or_else(|| self.value(value, step)),
+                LabelFormat::Clock => time_at(value)
+                    .map(clock)
+                    .unwrap_or_else(|| self.value(value, step)),
+                _ => self.value(value, step),
+            })
+            .collect()
+    }
+
+    /// A single value, with as many decimal places as `step` needs in the auto format.
+    /// Time formats write plain numbers.
+    pub fn value(self, value: f64, step: f64) -> String {
+        match self {
+            LabelFormat::Fixed(places) => format!("{value:.places$}"),
+            LabelFormat::Si => si(value),
+            LabelFormat::Scientific => format!("{value:.2e}"),
+            LabelFormat::Auto | LabelFormat::Relative | LabelFormat::Clock => {
+                let places = -step.abs().log10().floor();
+                format!("{value:.*}", places.clamp(0.0, 6.0) as usize)
+            }
+        }
+    }
+}
+
+fn si(value: f64) -> String {
+    const PREFIXES: [(f64, &str); 8] = [
+        (1e12, "T"),
+        (1e9, "G"),
+        (1e6, "M"),
+        (1e3, "k"),
+        (1.0, ""),
+        (1e-3, "m"),
+        (1e-6, "µ"),
+        (1e-9, "n"),
+    ];
+
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let (scale, prefix) = PREFIXES
+        .iter()
+        .find(|(scale, _)| value.abs() >= *scale)
+        .unwrap_or(&PREFIXES[PREFIXES.len() - 1]);
+
+    let scaled = value / scale;
+    let places = 2 - (scaled.abs().log10().floor() as i32).clamp(0, 2);
+    format!("{scaled:.*}{prefix}", places as usize)
+}
+
+/// Signed offset from now in the largest whole unit, such as -30s or -5m
+fn relative(time: SystemTime, now: SystemTime) -> String {
+    let (sign, offset) = match time.duration_since(now) {
+        Ok(ahead) => ("+", ahead),
+        Err(behind) => ("-", behind.duration()),
+    };
+
+    match offset.as_secs_f64().round() as u64 {
+        0 => "0s".to_string(),
+        secs if secs < 120 => format!("{sign}{secs}s"),
+        secs if secs < 7200 => format!("{sign}{}m", secs / 60),
+        secs => format!("{sign}{}h", secs / 3600),
+    }
+}
+
+/// Local time of day as HH:MM:SS
+fn clock(time: SystemTime) -> String {
+    DateTime::<Local>::from(time).format("%H:%M:%S").to_string()
+}
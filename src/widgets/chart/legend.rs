@@ -0,0 +1,216 @@
+use std::{num::ParseIntError, str::FromStr};
+
+use clap::{ArgEnum, Args};
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Style,
+    text::Spans,
+    widgets::{Block as TuiBlock, Borders, Clear, Widget},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, ArgEnum)]
+pub enum LegendPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Beside the plot, which narrows to make room
+    OutsideRight,
+    /// Below the plot, which shortens to make room
+    OutsideBottom,
+    Hidden,
+}
+
+/// A statistic shown after a legend entry, over the series' retained samples
+#[derive(Debug, Copy, Clone, PartialEq, ArgEnum)]
+pub enum LegendStat {
+    Last,
+    Min,
+    Max,
+    Avg,
+}
+
+impl LegendStat {
+    fn of(self, points: &[(f64, f64)]) -> Option<f64> {
+        let values = points.iter().map(|(_, y)| *y);
+        match self {
+            LegendStat::Last => points.last().map(|(_, y)| *y),
+            LegendStat::Min => values.reduce(f64::min),
+            LegendStat::Max => values.reduce(f64::max),
+            LegendStat::Avg => match points.len() {
+                0 => None,
+                n => Some(values.sum::<f64>() / n as f64),
+            },
+        }
+    }
+}
+
+/// A minimum pane size, as <WIDTH>x<HEIGHT>
+#[derive(Debug, Copy, Clone)]
+pub struct Size {
+    width: u16,
+    height: u16,
+}
+
+impl FromStr for Size {
+    type Err = ParseIntError;
+
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let (width, height) = size.split_once('x').unwrap_or((size, "0"));
+        Ok(Size {
+            width: width.parse()?,
+            height: height.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-CHART-LEGEND")]
+pub struct Legend {
+    /// Where to draw the legend
+    #[clap(
+        name = "LEGEND_POSITION",
+        long = "legend-position",
+        arg_enum,
+        default_value = "top-right"
+    )]
+    position: LegendPosition,
+
+    /// Hide the legend while the chart is smaller than <WIDTH>x<HEIGHT>
+    #[clap(name = "LEGEND_HIDE_BELOW", long = "legend-hide-below")]
+    hide_below: Option<Size>,
+
+    /// Statistics to show after each entry, such as `last,min,max`
+    #[clap(
+        name = "LEGEND_STATS",
+        long = "legend-stats",
+        multiple_values = true,
+        use_value_delimiter = true,
+        arg_enum
+    )]
+    stats: Vec<LegendStat>,
+}
+
+impl Legend {
+    /// The chosen statistics of `points`, written by `format`, as text to follow an entry's name.
+    /// The latest value comes first, then the others in parentheses, such as ` 54.1 (min 41 / max 78)`.
+    pub fn stats(&self, points: &[(f64, f64)], format: impl Fn(f64) -> String) -> String {
+        let mut text = String::new();
+        let mut others = vec![];
+        for stat in &self.stats {
+            let value = match stat.of(points) {
+                Some(value) => format(value),
+                None => continue,
+            };
+
+            match stat {
+                LegendStat::Last => text += &format!(" {value}"),
+                LegendStat::Min => others.push(format!("min {value}")),
+                LegendStat::Max => others.push(format!("max {value}")),
+                LegendStat::Avg => others.push(format!("avg {value}")),
+            }
+        }
+
+        if !others.is_empty() {
+            text += &format!(" ({})", others.join(" / "));
+        }
+        text
+    }
+
+    /// Split `area` into the chart's area and the legend's, given the legend's size.
+    /// `graph_area` finds where data is plotted within the chart's area, for legends placed over it.
+    /// The legend is None if hidden, or if it doesn't fit.
+    pub fn layout(
+        &self,
+        area: Rect,
+        (width, height): (u16, u16),
+        graph_area: impl Fn(Rect) -> Rect,
+    ) -> (Rect, Option<Rect>) {
+        let too_small = self
+            .hide_below
+            .is_some_and(|min| area.width < min.width || area.height < min.height);
+        if too_small || width <= 2 {
+            return (area, None);
+        }
+
+        match self.position {
+            LegendPosition::Hidden => (area, None),
+            LegendPosition::OutsideRight if width < area.width => (
+                Rect {
+                    width: area.width - width,
+                    ..area
+                },
+                Some(Rect::new(
+                    area.right() - width,
+                    area.top(),
+                    width,
+                    height.min(area.height),
+                )),
+            ),
+            LegendPosition::OutsideBottom if height < area.height => (
+                Rect {
+                    height: area.height - height,
+                    ..area
+                },
+                Some(Rect::new(
+                    area.left(),
+                    area.bottom() - height,
+                    width.min(area.width),
+                    height,
+                )),
+            ),
+            LegendPosition::OutsideRight | LegendPosition::OutsideBottom => (area, None),
+            corner => {
+                let graph = graph_area(area);
+                if width >= graph.width || height >= graph.height {
+                    return (area, None);
+                }
+
+                let x = match corner {
+                    LegendPosition::TopLeft | LegendPosition::BottomLeft => graph.left(),
+                    _ => graph.right() - width,
+                };
+                let y = match corner {
+                    LegendPosition::TopLeft | LegendPosition::TopRight => graph.top(),
+                    _ => graph.bottom() - height,
+                };
+
+                (area, Some(Rect::new(x, y, width, height)))
+            }
+        }
+    }
+}
+
+/// A bordered list of dataset entries, one per row
+pub struct LegendWidget<'a> {
+    pub entries: Vec<Spans<'a>>,
+    pub style: Style,
+}
+
+impl LegendWidget<'_> {
+    /// Width and height needed to show every entry
+    pub fn size(&self) -> (u16, u16) {
+        let width = self
+            .entries
+            .iter()
+            .map(Spans::width)
+            .max()
+            .unwrap_or_default();
+        (width as u16 + 2, self.entries.len() as u16 + 2)
+    }
+}
+
+impl Widget for LegendWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let block = TuiBlock::default().borders(Borders::ALL).style(self.style);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        for (entry, y) in self.entries.iter().zip(inner.top()..inner.bottom()) {
+            buf.set_spans(inner.left(), y, entry, inner.width);
+        }
+    }
+}
@@ -0,0 +1,863 @@
+mod bars;
+mod history;
+mod labels;
+mod legend;
+mod smooth;
+mod thresholds;
+
+use std::{borrow::Cow, collections::BTreeSet, num::ParseFloatError};
+
+use crate::{
+    graph_type::GraphType,
+    marker::Marker,
+    pattern::Pattern,
+    record::{with_unit, Record},
+    style::{Color, Modifier as StyleModifier},
+    threads::{
+        control::{Command, Scroll},
+        main::{Frame, InputBuffer, View},
+    },
+    Style,
+};
+
+use clap::{ArgEnum, Args};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+use tui::{
+    layout::{Constraint, Rect},
+    style::{Color as TuiColor, Modifier, Style as TuiStyle},
+    text::{Span, Spans},
+    widgets::{Axis as TuiAxis, Block as TuiBlock, Chart as TuiChart, Dataset},
+};
+
+use self::{
+    bars::Bars,
+    history::{History, SeriesKey},
+    labels::LabelFormat,
+    legend::{Legend, LegendWidget},
+    smooth::Smoothing,
+    thresholds::{Guides, Thresholds, Zones},
+};
+use super::{block::Block, error::draw_error};
+
+/// Factor the X axis is zoomed by per mouse wheel step
+const WHEEL_ZOOM: f64 = 1.25;
+
+/// Default key bindings, as `(key, command)`
+pub const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("left", "scroll-x -1"),
+    ("h", "scroll-x -1"),
+    ("right", "scroll-x +1"),
+    ("l", "scroll-x +1"),
+    ("pageup", "scroll-x -10"),
+    ("ctrl-b", "scroll-x -10"),
+    ("pagedown", "scroll-x +10"),
+    ("ctrl-f", "scroll-x +10"),
+    ("home", "scroll-x 0"),
+    ("g", "scroll-x 0"),
+    ("end", "scroll-x end"),
+    ("G", "scroll-x end"),
+];
+
+Style!(
+    ChartStyle,
+    "OPTIONS-CHART-STYLE",
+    "CHART_FG",
+    "CHART_BG",
+    "CHART_ADD_MODIFIER",
+    "CHART_SUB_MODIFIER",
+    "chart-fg",
+    "chart-bg",
+    "chart-add-modifier",
+    "chart-sub-modifier",
+);
+
+#[derive(Debug, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-CHART")]
+pub struct Chart {
+    /// Dataset colors, assigned sequentially. Wraps if dataset count exceeds color count.
+    #[clap(
+        name = "COLOR",
+        short = 'c',
+        long = "color",
+        multiple_values = true,
+        arg_enum
+    )]
+    colors: Vec<Color>,
+
+    /// Samples retained per dataset
+    #[clap(name = "HISTORY", long = "history", default_value = "100")]
+    history: usize,
+
+    /// Dataset markers, assigned sequentially like colors
+    #[clap(
+        name = "MARKER",
+        long = "marker",
+        multiple_values = true,
+        arg_enum,
+        default_value = "braille"
+    )]
+    markers: Vec<Marker>,
+
+    /// Whether to join dataset samples with lines or plot them as points, assigned sequentially like colors
+    #[clap(
+        name = "GRAPH_TYPE",
+        long = "graph-type",
+        multiple_values = true,
+        arg_enum,
+        default_value = "line"
+    )]
+    graph_types: Vec<GraphType>,
+
+    /// Style datasets whose names match a glob or /regex/, as PATTERN=ATTRIBUTE,...
+    /// Attributes are a color, marker, graph type or modifiers, e.g. '*Tccd*=red,bold';
+    /// later rules override earlier ones.
+    #[clap(
+        name = "SERIES_STYLE",
+        long = "series-style",
+        multiple_occurrences = true,
+        parse(try_from_str = series_style_from_str)
+    )]
+    series_styles: Vec<SeriesStyle>,
+
+    /// Smooth each series with a trailing moving average over N samples, as sma:N,
+    /// or an exponentially weighted moving average with smoothing factor A, as ewma:A
+    #[clap(name = "SMOOTH", long = "smooth")]
+    smooth: Option<Smoothing>,
+
+    /// Also draw each series unsmoothed, faintly beneath the smoothed one
+    #[clap(name = "SMOOTH_RAW", long = "smooth-raw", requires = "SMOOTH")]
+    smooth_raw: bool,
+
+    #[clap(flatten)]
+    style: ChartStyle,
+
+    #[clap(flatten)]
+    block: Block,
+
+    #[clap(flatten)]
+    legend: Legend,
+
+    #[clap(flatten)]
+    thresholds: Thresholds,
+
+    #[clap(flatten)]
+    x_axis: XAxis,
+
+    #[clap(flatten)]
+    y_axis: YAxis,
+}
+
+/// Attributes applied to series whose names match a pattern
+#[derive(Debug, Clone)]
+pub struct SeriesStyle {
+    pattern: Pattern,
+    color: Option<Color>,
+    marker: Option<Marker>,
+    graph_type: Option<GraphType>,
+    modifiers: Vec<StyleModifier>,
+}
+
+fn series_style_from_str(rule: &str) -> Result<SeriesStyle, String> {
+    let (pattern, attributes) = rule
+        .rsplit_once('=')
+        .ok_or_else(|| format!("Expected <PATTERN>=<ATTRIBUTE>,...: {rule}"))?;
+
+    let mut style = SeriesStyle {
+        pattern: pattern.parse()?,
+        color: None,
+        marker: None,
+        graph_type: None,
+        modifiers: vec![],
+    };
+
+    for attribute in attributes.split(',').map(str::trim) {
+        if let Ok(color) = Color::from_str(attribute, true) {
+            style.color = Some(color);
+        } else if let Ok(marker) = Marker::from_str(attribute, true) {
+            style.marker = Some(marker);
+        } else if let Ok(graph_type) = GraphType::from_str(attribute, true) {
+            style.graph_type = Some(graph_type);
+        } else if let Ok(modifier) = StyleModifier::from_str(attribute, true) {
+            style.modifiers.push(modifier);
+        } else {
+            return Err(format!("Invalid series style attribute: {attribute}"));
+        }
+    }
+
+    Ok(style)
+}
+
+fn label_count_from_str(count: &str) -> Result<usize, String> {
+    match count.parse() {
+        Ok(1) => Err("A single label can't mark both ends of an axis; use 0 or at least 2".into()),
+        Ok(count) => Ok(count),
+        Err(e) => Err(format!("{e}")),
+    }
+}
+
+fn bounds_from_str(bounds: &str) -> Result<[f64; 2], ParseFloatError> {
+    let mut bounds = bounds.split("..");
+    Ok([
+        bounds.next().unwrap_or_default().parse()?,
+        bounds.next().unwrap_or_default().parse()?,
+    ])
+}
+
+#[derive(Debug, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-CHART-X-AXIS")]
+pub struct XAxis {
+    /// X axis label
+    #[clap(name = "X_AXIS_TITLE", long = "x-axis-title")]
+    title: Option<String>,
+
+    /// X axis value range; follows the retained samples if unset.
+    /// Counts records from the oldest one retained, unless the input gives its own X coordinates
+    #[clap(name = "X_AXIS_BOUNDS", long = "x-axis-bounds", parse(try_from_str = bounds_from_str))]
+    bounds: Option<[f64; 2]>,
+
+    /// How the X axis follows retained samples
+    #[clap(
+        name = "X_AXIS_MODE",
+        long = "x-axis-mode",
+        arg_enum,
+        default_value = "scroll"
+    )]
+    mode: XAxisMode,
+
+    /// Number of X axis tick labels; 0 for none, otherwise at least 2 for both ends
+    #[clap(
+        name = "X_AXIS_LABELS",
+        long = "x-axis-labels",
+        default_value = "3",
+        parse(try_from_str = label_count_from_str)
+    )]
+    labels: usize,
+
+    /// X axis tick label format: auto, .N for N decimal places, si, e,
+    /// relative for time since arrival such as -30s, or clock for local arrival time
+    #[clap(name = "X_AXIS_FORMAT", long = "x-axis-format", default_value = "auto")]
+    format: LabelFormat,
+
+    #[clap(flatten)]
+    style: XAxisStyle,
+}
+
+#[derive(Debug, Copy, Clone, ArgEnum)]
+pub enum XAxisMode {
+    /// Keep the latest sample at the right edge
+    Scroll,
+    /// Fill the window from left to right, then start a new one
+    Fixed,
+}
+
+impl XAxis {
+    /// X axis range fitting samples with their own X coordinates,
+    /// or else covering as many samples as the history retains
+    fn bounds(&self, history: &History) -> [f64; 2] {
+        let explicit_x = history.explicit_x_range();
+
+        if let Some([min, max]) = self.bounds {
+            return match explicit_x {
+                Some(_) => [min, max],
+                None => {
+                    let start = history.start() as f64;
+                    [start + min, start + max]
+                }
+            };
+        }
+
+        if let Some([min, max]) = explicit_x {
+            return if max > min {
+                [min, max]
+            } else {
+                [min - 0.5, max + 0.5]
+            };
+        }
+
+        let width = history.capacity().saturating_sub(1).max(1) as f64;
+        let latest = history.latest().unwrap_or_default();
+        match self.mode {
+            XAxisMode::Scroll => [latest - width, latest],
+            XAxisMode::Fixed => {
+                let start = (latest / (width + 1.0)).floor() * (width + 1.0);
+                [start, start + width]
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-CHART-Y-AXIS")]
+pub struct YAxis {
+    /// Y axis label
+    #[clap(name = "Y_AXIS_TITLE", long = "y-axis-title")]
+    title: Option<String>,
+
+    /// Y axis value range. Either end may be auto to fit the visible samples,
+    /// as in auto..100.0 or 0.0..auto; auto alone fits both ends
+    #[clap(name = "Y_AXIS_BOUNDS", long = "y-axis-bounds", parse(try_from_str = auto_bounds_from_str), default_value = "0.0..1.0")]
+    bounds: [Bound; 2],
+
+    /// Headroom added beyond automatic bounds, as a fraction of the range
+    #[clap(
+        name = "Y_AXIS_PADDING",
+        long = "y-axis-padding",
+        default_value = "0.05"
+    )]
+    padding: f64,
+
+    /// Never shrink automatic bounds, so the scale doesn't jitter
+    #[clap(name = "Y_AXIS_STICKY", long = "y-axis-sticky")]
+    sticky: bool,
+
+    /// Number of Y axis tick labels; 0 for none, otherwise at least 2 for both ends
+    #[clap(
+        name = "Y_AXIS_LABELS",
+        long = "y-axis-labels",
+        default_value = "3",
+        parse(try_from_str = label_count_from_str)
+    )]
+    labels: usize,
+
+    /// Y axis tick label format: auto, .N for N decimal places, si or e
+    #[clap(name = "Y_AXIS_FORMAT", long = "y-axis-format", default_value = "auto")]
+    format: LabelFormat,
+
+    #[clap(flatten)]
+    style: YAxisStyle,
+}
+
+/// One end of an axis range
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Bound {
+    Fixed(f64),
+    Auto,
+}
+
+fn auto_bounds_from_str(bounds: &str) -> Result<[Bound; 2], ParseFloatError> {
+    let bound = |bound: &str| match bound.trim() {
+        "auto" => Ok(Bound::Auto),
+        bound => bound.parse().map(Bound::Fixed),
+    };
+
+    match bounds.split_once("..") {
+        Some((min, max)) => Ok([bound(min)?, bound(max)?]),
+        None => Ok([bound(bounds)?, bound(bounds)?]),
+    }
+}
+
+impl YAxis {
+    /// Resolve the range, fitting automatic ends to the `[min, max]` of the visible samples.
+    /// In sticky mode, `fitted` accumulates the widest range seen so far.
+    fn bounds(&self, visible: Option<[f64; 2]>, fitted: &mut Option<[f64; 2]>) -> [f64; 2] {
+        if let Some([min, max]) = visible {
+            let [min, max] = match (*fitted, self.sticky) {
+                (Some([fitted_min, fitted_max]), true) => {
+                    [min.min(fitted_min), max.max(fitted_max)]
+                }
+                _ => [min, max],
+            };
+            *fitted = Some([min, max]);
+        }
+
+        let [min, max] = fitted.unwrap_or([0.0, 1.0]);
+        let padding = if max > min {
+            (max - min) * self.padding
+        } else {
+            0.5
+        };
+
+        let [lower, upper] = self.bounds;
+        let lower = match lower {
+            Bound::Fixed(lower) => lower,
+            Bound::Auto => min - padding,
+        };
+        let upper = match upper {
+            Bound::Fixed(upper) => upper,
+            Bound::Auto => max + padding,
+        };
+
+        [lower, upper]
+    }
+}
+
+Style!(
+    XAxisStyle,
+    "OPTIONS-CHART-X-AXIS-STYLE",
+    "X_AXIS_FG",
+    "X_AXIS_BG",
+    "X_AXIS_ADD_MODIFIER",
+    "X_AXIS_SUB_MODIFIER",
+    "x-axis-fg",
+    "x-axis-bg",
+    "x-axis-add-modifier",
+    "x-axis-sub-modifier",
+);
+
+Style!(
+    YAxisStyle,
+    "OPTIONS-CHART-Y-AXIS-STYLE",
+    "Y_AXIS_FG",
+    "Y_AXIS_BG",
+    "Y_AXIS_ADD_MODIFIER",
+    "Y_AXIS_SUB_MODIFIER",
+    "y-axis-fg",
+    "y-axis-bg",
+    "y-axis-add-modifier",
+    "y-axis-sub-modifier",
+);
+
+impl Chart {
+    pub fn view(self, buf: InputBuffer) -> impl View {
+        ChartView {
+            style: self.style.clone().into(),
+            x_axis_style: self.x_axis.style.clone().into(),
+            y_axis_style: self.y_axis.style.clone().into(),
+            title: None,
+            pan: 0,
+            max_pan: 0,
+            zoom: 1.0,
+            hidden: Default::default(),
+            legend: None,
+            fitted: None,
+            history: History::new(self.history),
+            chart: self,
+            buf,
+        }
+    }
+}
+
+struct ChartView {
+    chart: Chart,
+    buf: InputBuffer,
+    style: TuiStyle,
+    x_axis_style: TuiStyle,
+    y_axis_style: TuiStyle,
+    /// Block title set at runtime
+    title: Option<String>,
+    /// Samples the X axis is panned back from the latest
+    pan: u16,
+    /// Furthest the X axis can be panned back before running out of samples
+    max_pan: u16,
+    /// X axis magnification, relative to the configured bounds
+    zoom: f64,
+    /// Series toggled off from the legend
+    hidden: BTreeSet<SeriesKey>,
+    /// Where the legend was last drawn, and the series on each of its rows
+    legend: Option<(Rect, Vec<SeriesKey>)>,
+    /// Value range automatic Y bounds were last fitted to
+    fitted: Option<[f64; 2]>,
+    history: History,
+}
+
+impl View for ChartView {
+    fn draw(&mut self, f: &mut Frame) {
+        let buf = self.buf.borrow();
+
+        let [x_min, x_max] = self.chart.x_axis.bounds(&self.history);
+        let x_min = x_max - (x_max - x_min) / self.zoom;
+        let oldest = self.history.oldest().unwrap_or(x_min).min(x_min);
+        self.max_pan = (x_min - oldest).clamp(0.0, u16::MAX as f64) as u16;
+        self.pan = self.pan.min(self.max_pan);
+        let x_bounds = [x_min - self.pan as f64, x_max - self.pan as f64];
+
+        let visible = self
+            .history
+            .iter()
+            .filter(|series| !self.hidden.contains(&series.key))
+            .flat_map(|series| &series.points)
+            .filter(|(x, _)| (x_bounds[0]..=x_bounds[1]).contains(x))
+            .fold(None, |range: Option<[f64; 2]>, (_, y)| match range {
+                Some([min, max]) => Some([min.min(*y), max.max(*y)]),
+                None => Some([*y, *y]),
+            });
+        let y_bounds = self.chart.y_axis.bounds(visible, &mut self.fitted);
+
+        // Legend statistics are written to about a hundredth of the Y axis range
+        let stats_step = (y_bounds[1] - y_bounds[0]) / 100.0;
+
+        let rect = draw_error(f, f.size(), &buf);
+        // Bars are drawn separately, leaving their datasets only a legend entry
+        let mut bars = vec![];
+        let mut entries = vec![];
+        // Unsmoothed series, drawn beneath everything else
+        let mut raw = vec![];
+        // Runs above the critical level, drawn over their series
+        let mut critical = vec![];
+        let smoothed = self
+            .history
+            .iter()
+            .map(|series| match self.chart.smooth {
+                Some(smoothing) => Cow::Owned(smoothing.apply(&series.points)),
+                None => Cow::Borrowed(&series.points[..]),
+            })
+            .collect::<Vec<_>>();
+        let datasets = self
+            .history
+            .iter()
+            .zip(&smoothed)
+            .map(|(series, points)| {
+                let i = series.slot;
+                let mut color = cycle(&self.chart.colors, i)
+                    .map(Into::into)
+                    .unwrap_or(TuiColor::White);
+                let mut marker = cycle(&self.chart.markers, i).unwrap_or(Marker::Braille);
+                let mut graph_type = cycle(&self.chart.graph_types, i).unwrap_or(GraphType::Line);
+                let mut modifier = Modifier::empty();
+
+                for rule in &self.chart.series_styles {
+                    if rule.pattern.matches(series.name()) {
+                        color = rule.color.map(Into::into).unwrap_or(color);
+                        marker = rule.marker.unwrap_or(marker);
+                        graph_type = rule.graph_type.unwrap_or(graph_type);
+                        modifier |= rule.modifiers.iter().copied().sum();
+                    }
+                }
+                let style = TuiStyle::default().fg(color).add_modifier(modifier);
+                let faint = style.add_modifier(Modifier::DIM);
+                let shown = !self.hidden.contains(&series.key);
+
+                if self.chart.smooth_raw && shown {
+                    if let Marker::Bar = marker {
+                        bars.push(Bars {
+                            data: &series.points,
+                            x_bounds,
+                            y_bounds,
+                            style: faint,
+                        });
+                    } else {
+                        raw.push(
+                            Dataset::default()
+                                .marker(marker.into())
+                                .graph_type(graph_type.into())
+                                .style(faint)
+                                .data(&series.points),
+                        );
+                    }
+                }
+
+                // Hidden series keep their legend entry, so they can be shown again
+                let (style, data) = if !shown {
+                    (
+                        style.add_modifier(Modifier::DIM | Modifier::CROSSED_OUT),
+                        &[][..],
+                    )
+                } else if let Marker::Bar = marker {
+                    bars.push(Bars {
+                        data: points,
+                        x_bounds,
+                        y_bounds,
+                        style,
+                    });
+                    if let Some((color, runs)) = self.chart.thresholds.critical(points) {
+                        bars.extend(runs.map(|data| Bars {
+                            data,
+                            x_bounds,
+                            y_bounds,
+                            style: style.fg(color),
+                        }));
+                    }
+                    (style, &[][..])
+                } else {
+                    if let Some((color, runs)) = self.chart.thresholds.critical(points) {
+                        critical.extend(runs.map(|data| {
+                            Dataset::default()
+                                .marker(marker.into())
+                                .graph_type(graph_type.into())
+                                .style(style.fg(color))
+                                .data(data)
+                        }));
+                    }
+                    (style, &points[..])
+                };
+
+                let stats = self.chart.legend.stats(&series.points, |value| {
+                    with_unit(
+                        self.chart.y_axis.format.value(value, stats_step),
+                        series.unit.as_deref(),
+                    )
+                });
+                let label = self.history.label(series);
+                entries.push(Spans::from(vec![
+                    Span::styled(label.clone(), style),
+                    Span::styled(stats, style),
+                ]));
+
+                Dataset::default()
+                    .name(label)
+                    .marker(marker.into())
+                    .graph_type(graph_type.into())
+                    .style(style)
+                    .data(data)
+            })
+            .collect::<Vec<_>>();
+        let datasets = raw.into_iter().chain(datasets).chain(critical).collect();
+
+        let x_labels = self
+            .chart
+            .x_axis
+            .format
+            .labels(x_bounds, self.chart.x_axis.labels, |x| {
+                self.history.time_at(x)
+            });
+        let unit = self.history.unit();
+        let y_labels = self
+            .chart
+            .y_axis
+            .format
+            .labels(y_bounds, self.chart.y_axis.labels, |_| None)
+            .into_iter()
+            .map(|label| with_unit(label, unit))
+            .collect::<Vec<_>>();
+
+        let x_axis = TuiAxis::default().bounds(x_bounds).style(self.x_axis_style);
+        let x_axis = if let Some(title) = self.chart.x_axis.title.as_deref() {
+            x_axis.title(title)
+        } else {
+            x_axis
+        };
+        let x_axis = if !x_labels.is_empty() {
+            x_axis.labels(x_labels.iter().cloned().map(Span::raw).collect())
+        } else {
+            x_axis
+        };
+
+        let y_axis = TuiAxis::default().bounds(y_bounds).style(self.y_axis_style);
+        let y_axis = match (self.chart.y_axis.title.as_deref(), unit) {
+            (Some(title), Some(unit)) => y_axis.title(format!("{title} ({unit})")),
+            (Some(title), None) => y_axis.title(title),
+            (None, Some(unit)) => y_axis.title(unit),
+            (None, None) => y_axis,
+        };
+        let y_axis = if !y_labels.is_empty() {
+            y_axis.labels(y_labels.iter().cloned().map(Span::raw).collect())
+        } else {
+            y_axis
+        };
+
+        // The legend is drawn separately, so it can be placed anywhere
+        let widget = TuiChart::new(datasets)
+            .hidden_legend_constraints((Constraint::Length(0), Constraint::Length(0)))
+            .x_axis(x_axis)
+            .y_axis(y_axis)
+            .style(self.style);
+
+        let block: Option<TuiBlock> = self.chart.block.with_title(&self.title).try_into().ok();
+        let inner = block
+            .as_ref()
+            .map(|block| block.inner(rect))
+            .unwrap_or(rect);
+
+        let legend = LegendWidget {
+            entries,
+            style: self.style,
+        };
+        let (plot, legend_area) = self.chart.legend.layout(inner, legend.size(), |plot| {
+            graph_area(plot, &x_labels, &y_labels)
+        });
+        let graph_area = graph_area(plot, &x_labels, &y_labels);
+        self.legend = legend_area.map(|area| {
+            (
+                area,
+                self.history
+                    .iter()
+                    .map(|series| series.key.clone())
+                    .collect(),
+            )
+        });
+
+        if let Some(block) = block {
+            f.render_widget(block, rect);
+        }
+
+        // The chart applies its style over everything beneath it, so the rest goes on top
+        f.render_widget(widget, plot);
+        for bars in bars {
+            f.render_widget(bars, graph_area);
+        }
+        f.render_widget(
+            Guides {
+                thresholds: &self.chart.thresholds,
+                y_bounds,
+            },
+            graph_area,
+        );
+        f.render_widget(
+            Zones {
+                thresholds: &self.chart.thresholds,
+                y_bounds,
+            },
+            graph_area,
+        );
+
+        if let Some(area) = legend_area {
+            f.render_widget(legend, area);
+        }
+    }
+
+    fn command(&mut self, command: &Command) {
+        match command {
+            Command::Clear => {
+                self.history.clear();
+                self.fitted = None;
+                self.hidden.clear();
+            }
+            Command::ScrollX(scroll) => {
+                // Offsets count from the oldest sample, and positive deltas pan toward the latest
+                self.pan = match *scroll {
+                    Scroll::To(offset) => self.max_pan.saturating_sub(offset),
+                    Scroll::By(delta) => {
+                        (self.pan as i32 - delta).clamp(0, self.max_pan as i32) as u16
+                    }
+                    Scroll::End => 0,
+                }
+            }
+            Command::Title(title) => self.title = title.clone(),
+            _ => (),
+        }
+    }
+
+    fn record(&mut self, record: &Record) {
+        self.history.push(record);
+    }
+
+    fn mouse(&mut self, event: &MouseEvent) {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.zoom = (self.zoom * WHEEL_ZOOM).min(100.0),
+            MouseEventKind::ScrollDown => self.zoom = (self.zoom / WHEEL_ZOOM).max(0.01),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let (area, keys) = match &self.legend {
+                    Some(legend) => legend,
+                    None => return,
+                };
+
+                // Rows inside the legend's border each hold one series
+                if event.column <= area.left()
+                    || event.column >= area.right() - 1
+                    || event.row <= area.top()
+                {
+                    return;
+                }
+
+                if let Some(key) = keys.get((event.row - area.top() - 1) as usize) {
+                    if !self.hidden.remove(key) {
+                        self.hidden.insert(key.clone());
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// The `i`th of a sequentially assigned list, wrapping when it runs out
+fn cycle<T: Copy>(items: &[T], i: usize) -> Option<T> {
+    items.get(i % items.len().max(1)).copied()
+}
+
+/// Where `tui` plots data within the chart's drawing area, mirroring `Chart::layout`
+fn graph_area(area: Rect, x_labels: &[String], y_labels: &[String]) -> Rect {
+    if area.width == 0 || area.height == 0 {
+        return Rect::default();
+    }
+
+    let width = |label: &String| Span::raw(label.as_str()).width() as u16;
+
+    let mut x = area.left();
+    let mut y = area.bottom() - 1;
+
+    // X labels, then the X axis line
+    for _ in 0..2 {
+        if !x_labels.is_empty() && y > area.top() {
+            y -= 1;
+        }
+    }
+
+    // Y labels, or the part of the first X label left of the Y axis, capped at a third of the width
+    let y_labels_width = y_labels.iter().map(width).max().unwrap_or_default();
+    let first_x_label_width = x_labels
+        .first()
+        .map(width)
+        .unwrap_or_default()
+        .saturating_sub(if y_labels.is_empty() { 0 } else { 1 });
+    x += y_labels_width.max(first_x_label_width).min(area.width / 3);
+
+    // Y axis line
+    if !y_labels.is_empty() && x + 1 < area.right() {
+        x += 1;
+    }
+
+    if x < area.right() && y > 1 {
+        Rect::new(x, area.top(), area.right() - x, y - area.top() + 1)
+    } else {
+        Rect::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::record::Sample;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        x_axis: XAxis,
+    }
+
+    fn x_axis(args: &[&str]) -> XAxis {
+        Cli::parse_from(std::iter::once(&"chart").chain(args)).x_axis
+    }
+
+    /// A history holding `records` records of one series, each with its X coordinate if `x` is set
+    fn history(capacity: usize, records: usize, x: bool) -> History {
+        let mut history = History::new(capacity);
+        for i in 0..records {
+            history.push(&Record {
+                samples: vec![Sample {
+                    name: "a".to_string(),
+                    value: i as f64,
+                    x: x.then_some(1000.0 + i as f64),
+                    unit: None,
+                }],
+                ..Default::default()
+            });
+        }
+        history
+    }
+
+    #[test]
+    fn x_bounds_follow_retained_samples() {
+        let history = history(100, 250, false);
+        assert_eq!(x_axis(&[]).bounds(&history), [150.0, 249.0]);
+        assert_eq!(
+            x_axis(&["--x-axis-mode", "fixed"]).bounds(&history),
+            [200.0, 299.0]
+        );
+    }
+
+    #[test]
+    fn explicit_x_bounds_count_from_oldest_retained_record() {
+        let x_axis = x_axis(&["--x-axis-bounds", "0..200"]);
+        assert_eq!(x_axis.bounds(&history(100, 10, false)), [0.0, 200.0]);
+
+        // Once the history is full, the window moves along with it
+        let history = history(100, 250, false);
+        assert_eq!(x_axis.bounds(&history), [150.0, 350.0]);
+        assert_eq!(history.oldest(), Some(150.0));
+        assert_eq!(history.latest(), Some(249.0));
+    }
+
+    #[test]
+    fn explicit_x_bounds_for_explicit_x_input() {
+        let history = history(100, 250, true);
+        assert_eq!(x_axis(&[]).bounds(&history), [1150.0, 1249.0]);
+        assert_eq!(
+            x_axis(&["--x-axis-bounds", "1000..1300"]).bounds(&history),
+            [1000.0, 1300.0]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use std::str::FromStr;
+
+/// A filter applied to each series' retained samples before drawing
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Smoothing {
+    /// Trailing simple moving average over this many samples
+    Sma(usize),
+    /// Exponentially weighted moving average with this smoothing factor
+    Ewma(f64),
+}
+
+impl FromStr for Smoothing {
+    type Err = String;
+
+    fn from_str(smoothing: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Expected sma:<N> or ewma:<A> with 0 < A <= 1: {smoothing}");
+
+        match smoothing.split_once(':').ok_or_else(invalid)? {
+            ("sma", window) => match window.parse() {
+                Ok(window) if window > 0 => Ok(Smoothing::Sma(window)),
+                _ => Err(invalid()),
+            },
+            ("ewma", alpha) => match alpha.parse() {
+                Ok(alpha) if alpha > 0.0 && alpha <= 1.0 => Ok(Smoothing::Ewma(alpha)),
+                _ => Err(invalid()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Smoothing {
+    /// `points` with their Y values smoothed
+    pub fn apply(self, points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+        match self {
+            Smoothing::Sma(window) => {
+                let mut sum = 0.0;
+                points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (x, y))| {
+                        sum += y;
+                        if i >= window {
+                            sum -= points[i - window].1;
+                        }
+                        (*x, sum / (i + 1).min(window) as f64)
+                    })
+                    .collect()
+            }
+            Smoothing::Ewma(alpha) => {
+                let mut average = None;
+                points
+                    .iter()
+                    .map(|(x, y)| {
+                        let next =
+                            average.map_or(*y, |average| alpha * y + (1.0 - alpha) * average);
+                        average = Some(next);
+                        (*x, next)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ys(points: &[(f64, f64)]) -> Vec<f64> {
+        points.iter().map(|(_, y)| *y).collect()
+    }
+
+    const POINTS: [(f64, f64); 5] = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0), (4.0, 3.0)];
+
+    #[test]
+    fn parse() {
+        assert_eq!("sma:10".parse(), Ok(Smoothing::Sma(10)));
+        assert_eq!("ewma:0.2".parse(), Ok(Smoothing::Ewma(0.2)));
+        assert_eq!("ewma:1".parse(), Ok(Smoothing::Ewma(1.0)));
+
+        for invalid in [
+            "sma", "sma:0", "sma:-1", "sma:x", "ewma:0", "ewma:1.5", "wma:3",
+        ] {
+            assert!(invalid.parse::<Smoothing>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn sma_averages_trailing_window() {
+        let smoothed = Smoothing::Sma(2).apply(&POINTS);
+        assert_eq!(ys(&smoothed), vec![1.0, 2.0, 4.0, 6.0, 5.0]);
+
+        // X coordinates are kept
+        let xs = smoothed.iter().map(|(x, _)| *x).collect::<Vec<_>>();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        // A window wider than the samples averages everything so far
+        assert_eq!(
+            ys(&Smoothing::Sma(10).apply(&POINTS)),
+            vec![1.0, 2.0, 3.0, 4.0, 3.8]
+        );
+        assert_eq!(ys(&Smoothing::Sma(1).apply(&POINTS)), ys(&POINTS));
+    }
+
+    #[test]
+    fn ewma_weights_latest_samples() {
+        assert_eq!(
+            ys(&Smoothing::Ewma(0.5).apply(&POINTS)),
+            vec![1.0, 2.0, 3.5, 5.25, 4.125]
+        );
+        assert_eq!(ys(&Smoothing::Ewma(1.0).apply(&POINTS)), ys(&POINTS));
+    }
+
+    #[test]
+    fn empty() {
+        assert!(Smoothing::Sma(3).apply(&[]).is_empty());
+        assert!(Smoothing::Ewma(0.5).apply(&[]).is_empty());
+    }
+}
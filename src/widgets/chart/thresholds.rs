@@ -0,0 +1,182 @@
+use clap::{ArgEnum, Args};
+use tui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color as TuiColor, Style},
+    symbols::line,
+    widgets::Widget,
+};
+
+use crate::style::Color;
+
+/// A Y value with an optional color, as <VALUE>[:<COLOR>]
+#[derive(Debug, Copy, Clone)]
+pub struct Level {
+    value: f64,
+    color: Option<Color>,
+}
+
+/// A band of Y values, either end of which may be open, as <FROM>..<TO>[:<COLOR>]
+#[derive(Debug, Copy, Clone)]
+pub struct Zone {
+    bounds: [Option<f64>; 2],
+    color: Option<Color>,
+}
+
+impl Zone {
+    fn contains(&self, value: f64) -> bool {
+        let [from, to] = self.bounds;
+        from.is_none_or(|from| value >= from) && to.is_none_or(|to| value <= to)
+    }
+}
+
+fn split_color(arg: &str) -> Result<(&str, Option<Color>), String> {
+    match arg.rsplit_once(':') {
+        Some((value, color)) => Ok((value, Some(Color::from_str(color, true)?))),
+        None => Ok((arg, None)),
+    }
+}
+
+fn level_from_str(level: &str) -> Result<Level, String> {
+    let (value, color) = split_color(level)?;
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid level: {level}"))?;
+
+    Ok(Level { value, color })
+}
+
+fn zone_from_str(zone: &str) -> Result<Zone, String> {
+    let (bounds, color) = split_color(zone)?;
+    let (from, to) = bounds
+        .split_once("..")
+        .ok_or_else(|| format!("Expected <FROM>..<TO>[:<COLOR>]: {zone}"))?;
+
+    let bound = |bound: &str| match bound.trim() {
+        "" => Ok(None),
+        bound => bound
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("Invalid zone bound: {bound}")),
+    };
+
+    Ok(Zone {
+        bounds: [bound(from)?, bound(to)?],
+        color,
+    })
+}
+
+#[derive(Debug, Clone, Args)]
+#[clap(next_help_heading = "OPTIONS-CHART-THRESHOLDS")]
+pub struct Thresholds {
+    /// Draw a horizontal reference line, as VALUE[:COLOR], such as 85:red
+    #[clap(
+        name = "HLINE",
+        long = "hline",
+        multiple_occurrences = true,
+        parse(try_from_str = level_from_str)
+    )]
+    hlines: Vec<Level>,
+
+    /// Shade the background behind a band of values, as FROM..TO[:COLOR].
+    /// Either end may be left open, as in 85..:red
+    #[clap(
+        name = "ZONE",
+        long = "zone",
+        multiple_occurrences = true,
+        parse(try_from_str = zone_from_str)
+    )]
+    zones: Vec<Zone>,
+
+    /// Draw series in another color while above a level, as VALUE[:COLOR]; red by default
+    #[clap(name = "CRITICAL", long = "critical", parse(try_from_str = level_from_str))]
+    critical: Option<Level>,
+}
+
+impl Thresholds {
+    /// Runs of consecutive points above the critical level, with the color to draw them in
+    pub fn critical<'a>(
+        &self,
+        points: &'a [(f64, f64)],
+    ) -> Option<(TuiColor, impl Iterator<Item = &'a [(f64, f64)]>)> {
+        let critical = self.critical?;
+        let color = critical.color.map(Into::into).unwrap_or(TuiColor::Red);
+        let runs = points
+            .split(move |(_, y)| *y <= critical.value)
+            .filter(|run| !run.is_empty());
+
+        Some((color, runs))
+    }
+}
+
+/// Reference lines, drawn over the graph area once the chart is, in the cells its data left blank
+pub struct Guides<'a> {
+    pub thresholds: &'a Thresholds,
+    pub y_bounds: [f64; 2],
+}
+
+/// Zone backgrounds, applied over the graph area once the chart is drawn,
+/// since the chart resets the background of every cell it covers
+pub struct Zones<'a> {
+    pub thresholds: &'a Thresholds,
+    pub y_bounds: [f64; 2],
+}
+
+impl Widget for Zones<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [y_min, y_max] = self.y_bounds;
+        if area.width == 0 || area.height == 0 || y_max <= y_min {
+            return;
+        }
+
+        // Y value at each row, from the bottom, placed as the chart places points
+        let span = area.height.saturating_sub(1).max(1) as f64;
+        let rows = (area.top()..area.bottom())
+            .rev()
+            .enumerate()
+            .map(|(i, row)| (row, y_min + (y_max - y_min) * i as f64 / span));
+
+        for (row, value) in rows {
+            let zone = self
+                .thresholds
+                .zones
+                .iter()
+                .rev()
+                .find(|zone| zone.contains(value));
+
+            if let Some(zone) = zone {
+                let color = zone.color.map(Into::into).unwrap_or(TuiColor::DarkGray);
+                buf.set_style(
+                    Rect::new(area.left(), row, area.width, 1),
+                    Style::default().bg(color),
+                );
+            }
+        }
+    }
+}
+
+impl Widget for Guides<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [y_min, y_max] = self.y_bounds;
+        if area.width == 0 || area.height == 0 || y_max <= y_min {
+            return;
+        }
+
+        let step = (y_max - y_min) / area.height.saturating_sub(1).max(1) as f64;
+        for hline in &self.thresholds.hlines {
+            if !(y_min..=y_max).contains(&hline.value) {
+                continue;
+            }
+
+            let color = hline.color.map(Into::into).unwrap_or(TuiColor::DarkGray);
+            let row = area.bottom() - 1 - ((hline.value - y_min) / step).round() as u16;
+            for column in area.left()..area.right() {
+                let cell = buf.get_mut(column, row);
+                if cell.symbol == " " {
+                    cell.set_symbol(line::HORIZONTAL).set_fg(color);
+                }
+            }
+        }
+    }
+}
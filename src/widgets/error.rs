@@ -0,0 +1,37 @@
+use tui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Paragraph, Wrap},
+};
+
+use crate::{record::Record, threads::main::Frame};
+
+/// Draw the record's error, if any, along the bottom of `rect`.
+/// Returns the area left over for the widget itself.
+pub fn draw_error(f: &mut Frame, rect: Rect, record: &Record) -> Rect {
+    let error = match &record.error {
+        Some(error) => error,
+        None => return rect,
+    };
+
+    let height = (error.lines().count() as u16).min(rect.height / 2);
+    let rect = Rect {
+        height: rect.height - height,
+        ..rect
+    };
+
+    let widget = Paragraph::new(error.as_str())
+        .style(Style::default().fg(Color::Red))
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(
+        widget,
+        Rect {
+            y: rect.bottom(),
+            height,
+            ..rect
+        },
+    );
+
+    rect
+}
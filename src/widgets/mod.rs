@@ -1,3 +1,4 @@
 pub mod paragraph;
 pub mod block;
 pub mod chart;
+pub mod error;
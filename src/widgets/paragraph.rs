@@ -1,13 +1,46 @@
 use clap::Args;
-use tui::widgets::{Paragraph as TuiParagraph, Wrap as TuiWrap};
+use crossterm::event::{MouseEvent, MouseEventKind};
+use tui::{
+    style::Style as TuiStyle,
+    text::Text,
+    widgets::{Block as TuiBlock, Paragraph as TuiParagraph, Wrap as TuiWrap},
+};
 
 use crate::{
     alignment::Alignment,
-    threads::main::{FnDraw, Frame, InputBuffer},
+    text::{ansi_to_text, markup_to_text},
+    threads::{
+        control::{self, Command},
+        main::{Frame, InputBuffer, View},
+    },
     Style,
 };
 
-use super::block::Block;
+use super::{block::Block, error::draw_error};
+
+/// Lines scrolled per mouse wheel step
+const WHEEL_LINES: i32 = 3;
+
+/// Default key bindings, as `(key, command)`
+pub const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("up", "scroll-y -1"),
+    ("k", "scroll-y -1"),
+    ("down", "scroll-y +1"),
+    ("j", "scroll-y +1"),
+    ("left", "scroll-x -1"),
+    ("h", "scroll-x -1"),
+    ("right", "scroll-x +1"),
+    ("l", "scroll-x +1"),
+    ("pageup", "scroll-y -10"),
+    ("ctrl-b", "scroll-y -10"),
+    ("pagedown", "scroll-y +10"),
+    ("ctrl-f", "scroll-y +10"),
+    ("space", "scroll-y +10"),
+    ("home", "scroll-y 0"),
+    ("g", "scroll-y 0"),
+    ("end", "scroll-y end"),
+    ("G", "scroll-y end"),
+];
 
 Style!(
     ParagraphStyle,
@@ -29,6 +62,14 @@ pub struct Paragraph {
     #[clap(short, long, arg_enum)]
     alignment: Option<Alignment>,
 
+    /// Render ANSI color and style escape sequences
+    #[clap(long)]
+    ansi: bool,
+
+    /// Render inline style tags, such as [fg=red,bold]hot[/]
+    #[clap(long, conflicts_with = "ansi")]
+    markup: bool,
+
     #[clap(flatten)]
     wrap: Wrap,
 
@@ -43,38 +84,122 @@ pub struct Paragraph {
 }
 
 impl Paragraph {
-    pub fn draw(self, buf: InputBuffer) -> impl FnDraw {
-        let style = self.style.into();
-        let block = self.block.try_into();
-
-        move |f: &mut Frame| {
-            let rect = f.size();
-            let buf = buf.borrow();
-
-            let widget = TuiParagraph::new(buf.as_str()).style(style).scroll((
+    pub fn view(self, buf: InputBuffer) -> impl View {
+        ParagraphView {
+            style: self.style.clone().into(),
+            scroll: (
                 self.scroll.y.unwrap_or_default(),
                 self.scroll.x.unwrap_or_default(),
-            ));
-
-            let widget = if let Ok(wrap) = self.wrap.try_into() {
-                widget.wrap(wrap)
-            } else {
-                widget
-            };
-
-            let widget = if let Ok(block) = block.clone() {
-                widget.block(block)
-            } else {
-                widget
-            };
-
-            let widget = if let Some(alignment) = self.alignment {
-                widget.alignment(alignment.into())
-            } else {
-                widget
-            };
-
-            f.render_widget(widget, rect);
+            ),
+            max_scroll: (0, 0),
+            title: None,
+            paragraph: self,
+            buf,
+        }
+    }
+}
+
+struct ParagraphView {
+    paragraph: Paragraph,
+    buf: InputBuffer,
+    style: TuiStyle,
+    /// Requested (y, x) scroll offset, kept as is when the content is too short for it
+    scroll: (u16, u16),
+    /// Furthest (y, x) offset within the content when last drawn
+    max_scroll: (u16, u16),
+    /// Block title set at runtime
+    title: Option<String>,
+}
+
+impl ParagraphView {
+    /// The scroll offset, kept within the content as last drawn
+    fn visible_scroll(&self) -> (u16, u16) {
+        (
+            self.scroll.0.min(self.max_scroll.0),
+            self.scroll.1.min(self.max_scroll.1),
+        )
+    }
+}
+
+impl View for ParagraphView {
+    fn draw(&mut self, f: &mut Frame) {
+        let buf = self.buf.borrow();
+        let rect = draw_error(f, f.size(), &buf);
+
+        let text = if self.paragraph.ansi {
+            ansi_to_text(&buf.text)
+        } else if self.paragraph.markup {
+            markup_to_text(&buf.text)
+        } else {
+            Text::raw(buf.text.as_str())
+        };
+
+        let block: Option<TuiBlock> = self.paragraph.block.with_title(&self.title).try_into().ok();
+        let inner = block
+            .as_ref()
+            .map(|block| block.inner(rect))
+            .unwrap_or(rect);
+        let wrap: Option<TuiWrap> = self.paragraph.wrap.try_into().ok();
+
+        // Keep scrolling within the content, so scrolling to the end lands on the last page
+        let width = text.width() as u16;
+        let height = if wrap.is_some() && inner.width > 0 {
+            text.lines
+                .iter()
+                .map(|line| (line.width() as u16).max(1).div_ceil(inner.width))
+                .sum()
+        } else {
+            text.height() as u16
+        };
+        self.max_scroll = (
+            height.saturating_sub(inner.height),
+            width.saturating_sub(inner.width),
+        );
+
+        let widget = TuiParagraph::new(text)
+            .style(self.style)
+            .scroll(self.visible_scroll());
+
+        let widget = if let Some(wrap) = wrap {
+            widget.wrap(wrap)
+        } else {
+            widget
+        };
+
+        let widget = if let Some(block) = block {
+            widget.block(block)
+        } else {
+            widget
+        };
+
+        let widget = if let Some(alignment) = self.paragraph.alignment {
+            widget.alignment(alignment.into())
+        } else {
+            widget
+        };
+
+        f.render_widget(widget, rect);
+    }
+
+    fn command(&mut self, command: &Command) {
+        match command {
+            // Relative scrolling starts from what's on screen
+            Command::ScrollX(scroll) => self.scroll.1 = scroll.apply(self.visible_scroll().1),
+            Command::ScrollY(scroll) => self.scroll.0 = scroll.apply(self.visible_scroll().0),
+            Command::Title(title) => self.title = title.clone(),
+            _ => (),
+        }
+    }
+
+    fn mouse(&mut self, event: &MouseEvent) {
+        match event.kind {
+            MouseEventKind::ScrollUp => {
+                self.command(&Command::ScrollY(control::Scroll::By(-WHEEL_LINES)))
+            }
+            MouseEventKind::ScrollDown => {
+                self.command(&Command::ScrollY(control::Scroll::By(WHEEL_LINES)))
+            }
+            _ => (),
         }
     }
 }